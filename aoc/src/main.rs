@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Result};
+use input_gen::{generator, GENERATORS};
+
+/// Usage: aoc gen <day> [--size <n>] [--seed <s>]
+///
+/// Prints a random input for the day. The same size and seed always give the same input. What the
+/// size means depends on the day, mostly it's the number of lines or the side of the map.
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    print!("{}", run(&args)?);
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<String> {
+    let mut args = args.iter();
+    match args.next().map(|a| a.as_str()) {
+        Some("gen") => {}
+        Some(other) => return Err(anyhow!("Unknown command: {}", other)),
+        None => return Err(anyhow!("Usage: aoc gen <day> [--size <n>] [--seed <s>]")),
+    }
+
+    let day = args.next().ok_or_else(|| anyhow!("gen needs a day"))?;
+    let number = day.trim_start_matches("day").parse::<u32>().map_err(|_| anyhow!("Invalid day: {}", day))?;
+    let generate = generator(number).ok_or_else(|| {
+        let days = GENERATORS.iter().map(|(d, _)| d.to_string()).collect::<Vec<_>>();
+        anyhow!("No generator for day {}, there are: {}", number, days.join(", "))
+    })?;
+
+    let mut size = 10;
+    let mut seed = 0;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a number", arg));
+        match arg.as_str() {
+            "--size" => size = value()?.parse().map_err(|_| anyhow!("Invalid size"))?,
+            "--seed" => seed = value()?.parse().map_err(|_| anyhow!("Invalid seed"))?,
+            other => return Err(anyhow!("Unknown argument: {}", other)),
+        }
+    }
+
+    Ok(generate(size, seed))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::run;
+    use input_gen::{day05, day13};
+    use anyhow::Result;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    pub fn test_gen() -> Result<()> {
+        assert_eq!(run(&args("gen 5 --size 3 --seed 42"))?, day05::generate(3, 42));
+        assert_eq!(run(&args("gen day05 --seed 42 --size 3"))?, day05::generate(3, 42));
+        assert_eq!(run(&args("gen 13"))?, day13::generate(10, 0));
+        assert_ne!(run(&args("gen 13 --seed 1"))?, run(&args("gen 13 --seed 2"))?);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let error = |s: &str| run(&args(s)).unwrap_err().to_string();
+        assert_eq!(error("gen 16"), "No generator for day 16, there are: 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 21, 22, 23, 24, 25");
+        assert_eq!(error("gen 5 --size"), "--size needs a number");
+        assert_eq!(error("gen 5 --size x"), "Invalid size");
+        assert_eq!(error("gen 5 --verbose"), "Unknown argument: --verbose");
+        assert_eq!(error("run 5"), "Unknown command: run");
+    }
+}
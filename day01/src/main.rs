@@ -1,8 +1,16 @@
-use anyhow::Result;
+use std::cmp::Reverse;
+
+use aho_corasick::AhoCorasick;
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
 
 use util::Input;
 
-static DIGIT_NAMES: &[&str] = &["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+// Vocabularies of digit tokens, where each token's index is its value.
+static ARABIC: &[&str] = &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+static ENGLISH: &[&str] = &["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+static GERMAN: &[&str] = &["null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun"];
+static ROMAN: &[&str] = &["", "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"];
 
 fn main() -> Result<()> {
     let input = Input::load("day01/input")?;
@@ -12,56 +20,77 @@ fn main() -> Result<()> {
 
     println!("Part 2:");
     println!("{}", part2(&input)?); // 55701
+
+    // Optionally also run with a comma-separated list of vocabularies, e.g. "arabic,german".
+    if let Some(names) = std::env::args().nth(1) {
+        let vocabularies = names.split(',').map(|name| {
+            vocabulary(name).ok_or_else(|| anyhow!("Unknown vocabulary {}, expected arabic, english, german or roman", name))
+        }).collect::<Result<Vec<_>>>()?;
+        println!("With {}:", names);
+        println!("{}", part(&input, &DigitMatcher::from_vocabularies(&vocabularies)?)?);
+    }
     Ok(())
 }
 
-fn digits_in_including_names(str: &str) -> Vec<u32> {
-    str
-        .chars()
-        .enumerate()
-        .map(|(index, ch)| {
-            // Try to parse the char as digit. If it fails, try to find if the current position contains a digit by name.
-            ch.to_digit(10).or({
-                DIGIT_NAMES.iter().enumerate().find(|&(_i, name)| {
-                    str[index..].find(name) == Some(0)
-                })
-                .map(|(name_index, _s)| name_index as u32)
-            })
-        })
-        .filter_map(|x| x)
-        .collect()
+fn vocabulary(name: &str) -> Option<&'static [&'static str]> {
+    match name {
+        "arabic" => Some(ARABIC),
+        "english" => Some(ENGLISH),
+        "german" => Some(GERMAN),
+        "roman" => Some(ROMAN),
+        _ => None,
+    }
 }
 
-fn digits_in(str: &str) -> Vec<u32> {
-    str
-        .chars()
-        .map(|c| c.to_digit(10))
-        .filter_map(|x| x)
-        .collect()
+/// Finds digit tokens in text with one linear scan. Every token of the vocabulary stands for a
+/// digit value, and overlapping tokens are all found ("eightwo" gives 8, 2). When several tokens
+/// start at the same position, the longest one wins.
+struct DigitMatcher {
+    automaton: AhoCorasick,
+    values: Vec<u32>,
 }
 
-fn part<F>(input: &Input, digit_finder: F) -> Result<u32> where F: Fn(&str) -> Vec<u32> {
-    let value = input.as_lines().fold(0, |acc, s| {
-        let digits = digit_finder(s);
-        let first_digit = digits.first().unwrap();
-        let last_digit = digits.last().unwrap();
+impl DigitMatcher {
+    fn new<'a>(vocabulary: impl IntoIterator<Item = (&'a str, u32)>) -> Result<DigitMatcher> {
+        let (tokens, values): (Vec<_>, Vec<_>) = vocabulary.into_iter().filter(|(token, _)| !token.is_empty()).unzip();
+        let automaton = AhoCorasick::new(tokens)?;
+        Ok(DigitMatcher { automaton, values })
+    }
+
+    /// Builds a matcher from vocabularies where each word's index is its value.
+    fn from_vocabularies(vocabularies: &[&[&'static str]]) -> Result<DigitMatcher> {
+        DigitMatcher::new(vocabularies.iter().flat_map(|words| words.iter().enumerate().map(|(value, word)| (*word, value as u32))))
+    }
+
+    fn digits(&self, s: &str) -> Vec<u32> {
+        let mut matches = self.automaton.find_overlapping_iter(s).collect_vec();
+        matches.sort_by_key(|m| (m.start(), Reverse(m.end())));
+        matches.dedup_by_key(|m| m.start());
+        matches.iter().map(|m| self.values[m.pattern().as_usize()]).collect()
+    }
+}
 
-        acc + 10 * first_digit + last_digit
-    });
-    Ok(value)
+fn part(input: &Input, matcher: &DigitMatcher) -> Result<u32> {
+    input.as_lines().try_fold(0, |acc, s| {
+        let digits = matcher.digits(s);
+        match (digits.first(), digits.last()) {
+            (Some(first_digit), Some(last_digit)) => Ok(acc + 10 * first_digit + last_digit),
+            _ => Err(anyhow!("No digits in \"{}\"", s)),
+        }
+    })
 }
 
 fn part1(input: &Input) -> Result<u32> {
-    part(input, digits_in)
+    part(input, &DigitMatcher::from_vocabularies(&[ARABIC])?)
 }
 
 fn part2(input: &Input) -> Result<u32> {
-    part(input, digits_in_including_names)
+    part(input, &DigitMatcher::from_vocabularies(&[ARABIC, ENGLISH])?)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2};
+    use crate::{part1, part2, DigitMatcher, ARABIC, ENGLISH, GERMAN, ROMAN};
     use util::Input;
     use anyhow::Result;
 
@@ -105,4 +134,28 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 55701);
         Ok(())
     }
+
+    #[test]
+    pub fn test_overlapping() -> Result<()> {
+        let matcher = DigitMatcher::from_vocabularies(&[ARABIC, ENGLISH])?;
+        assert_eq!(matcher.digits("eightwo"), vec![8, 2]);
+        assert_eq!(matcher.digits("xtwone3four"), vec![2, 1, 3, 4]);
+        assert_eq!(matcher.digits("oneight"), vec![1, 8]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_other_vocabularies() -> Result<()> {
+        let german = DigitMatcher::from_vocabularies(&[ARABIC, GERMAN])?;
+        assert_eq!(german.digits("xfünfzwei3achtneun"), vec![5, 2, 3, 8, 9]);
+
+        // The longest numeral wins at each position, and overlaps are still reported after it.
+        let roman = DigitMatcher::from_vocabularies(&[ROMAN])?;
+        assert_eq!(roman.digits("aIXbVc"), vec![9, 5]);
+        assert_eq!(roman.digits("VIII"), vec![8, 3, 2, 1]);
+
+        let custom = DigitMatcher::new([("uno", 1), ("zwo", 2), ("🍕", 8)])?;
+        assert_eq!(custom.digits("zwo🍕uno"), vec![2, 8, 1]);
+        Ok(())
+    }
 }
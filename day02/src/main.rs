@@ -1,66 +1,149 @@
-use anyhow::Result;
-use std::collections::HashMap;
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 
-use regex::Regex;
-
-use util::{Input, satisfies};
+use util::Input;
+use util::parse::{labelled, map, pair, parse, preceded, separated_list1, space1, tag, terminated, unsigned, word, ParseError, Parser};
 
+/// Usage: day02 [--bag "12 red, 13 green, 14 blue" | --bag-file <path>] [--report]
+///
+/// The bag decides which games are possible in part 1, and defaults to the puzzle's bag. With
+/// --report, the picks that make each game impossible are listed too.
 fn main() -> Result<()> {
     let input = Input::load("day02/input")?;
 
+    let mut bag = None;
+    let mut report = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bag" => bag = Some(args.next().ok_or_else(|| anyhow!("--bag needs a value"))?.parse()?),
+            "--bag-file" => {
+                let path = args.next().ok_or_else(|| anyhow!("--bag-file needs a path"))?;
+                bag = Some(Input::load(path)?.as_str().trim().parse()?);
+            }
+            "--report" => report = true,
+            other => return Err(anyhow!("Unknown argument: {}", other)),
+        }
+    }
+
     println!("Part 1:");
-    println!("{}", part1(&input)?);
+    match &bag {
+        Some(bag) => println!("{}", part1_with_bag(&input, bag)?),
+        None => println!("{}", part1(&input)?),
+    }
 
     println!("Part 2:");
     println!("{}", part2(&input)?);
+
+    if report {
+        let bag = bag.unwrap_or_else(Bag::puzzle);
+        println!("Impossible with {}:", bag);
+        for game in parse_games(&input)? {
+            for violation in game.impossible_picks(&bag) {
+                println!("Game {}: {}", game.id, violation);
+            }
+        }
+    }
     Ok(())
 }
 
-fn color_counts(game: &Game) -> HashMap<String, u32> {
-    let all_cubes = game.picks.iter().flat_map(|p| p.cubes.clone());
-    let cc = all_cubes.fold(HashMap::<String, u32>::new(), |acc, pick| {
-        let current_count = *acc.get(&pick.0).unwrap_or(&0u32);
-        if pick.1 > current_count {
-            let mut new_acc = acc.clone();
-            new_acc.insert(pick.0, pick.1);
-            new_acc
-        } else {
-            acc
-        }
-    });
-    cc
+/// Cube counts per colour, for any colour names.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Bag {
+    cubes: BTreeMap<String, u32>,
 }
 
-fn part1(input: &Input) -> Result<u32> {
-    let result = input.as_lines()
-        .map(|line| parse_game(line))
-        .filter(|game| {
-            let cc = color_counts(game);
+impl Bag {
+    /// The bag from the puzzle: 12 red cubes, 13 green cubes, and 14 blue cubes.
+    fn puzzle() -> Bag {
+        Bag { cubes: [("red", 12), ("green", 13), ("blue", 14)].into_iter().map(|(c, n)| (c.to_string(), n)).collect() }
+    }
 
-            // only 12 red cubes, 13 green cubes, and 14 blue cubes
-            let possible = satisfies(cc.get("red"), |x| x <= &12u32) &&
-                satisfies(cc.get("green"), |x| x <= &13u32) &&
-                satisfies(cc.get("blue"), |x| x <= &14u32);
+    /// The number of cubes of the colour, zero for colours not in the bag.
+    fn count(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
 
-            possible
-        })
-        .map(|game| game.id)
-        .sum();
-    Ok(result)
+    fn power(&self) -> u32 {
+        self.cubes.values().product()
+    }
 }
 
-fn part2(input: &Input) -> Result<u32> {
-    let result = input.as_lines()
-        .map(|line| parse_game(line))
-        .map(|game| {
-            let cc = color_counts(&game);
+impl FromStr for Bag {
+    type Err = anyhow::Error;
 
-            let power = cc.iter().fold(1u32, |acc, pair| acc * pair.1);
+    fn from_str(s: &str) -> Result<Self> {
+        let cubes = parse(separated_list1(cube(), tag(", ")), s).map_err(|e| anyhow!("Bag \"{}\": {}", s, e))?;
+        Ok(Bag { cubes: cubes.into_iter().collect() })
+    }
+}
 
-            power
-        })
-        .sum();
-    Ok(result)
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes = self.cubes.iter().map(|(color, count)| format!("{} {}", count, color)).collect::<Vec<_>>();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+/// A colour in a pick that needs more cubes than the bag has.
+#[derive(Debug, PartialEq, Eq)]
+struct Violation {
+    /// The 1-based index of the pick within the game.
+    pick: usize,
+    color: String,
+    count: u32,
+    available: u32,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pick {} shows {} {}, but the bag only has {}", self.pick, self.count, self.color, self.available)
+    }
+}
+
+impl Game {
+    /// The smallest bag that makes the game possible: the largest count seen of each colour.
+    fn minimal_bag(&self) -> Bag {
+        let mut bag = Bag::default();
+        for (color, count) in self.picks.iter().flat_map(|p| p.cubes.iter()) {
+            let max = bag.cubes.entry(color.clone()).or_insert(0);
+            *max = (*max).max(*count);
+        }
+        bag
+    }
+
+    /// Every pick and colour that shows more cubes than the bag holds.
+    fn impossible_picks(&self, bag: &Bag) -> Vec<Violation> {
+        self.picks.iter().enumerate().flat_map(|(idx, pick)| {
+            pick.cubes.iter()
+                .filter(|(color, count)| *count > bag.count(color))
+                .map(move |(color, count)| Violation { pick: idx + 1, color: color.clone(), count: *count, available: bag.count(color) })
+        }).collect()
+    }
+
+    fn is_possible(&self, bag: &Bag) -> bool {
+        self.impossible_picks(bag).is_empty()
+    }
+}
+
+fn parse_games(input: &Input) -> Result<Vec<Game>> {
+    Ok(input.as_lines().map(parse_game).collect::<Result<Vec<_>, _>>()?)
+}
+
+fn part1_with_bag(input: &Input, bag: &Bag) -> Result<u32> {
+    let games = parse_games(input)?;
+    Ok(games.iter().filter(|game| game.is_possible(bag)).map(|game| game.id).sum())
+}
+
+fn part1(input: &Input) -> Result<u32> {
+    part1_with_bag(input, &Bag::puzzle())
+}
+
+fn part2(input: &Input) -> Result<u32> {
+    let games = parse_games(input)?;
+    Ok(games.iter().map(|game| game.minimal_bag().power()).sum())
 }
 
 #[derive(PartialEq)]
@@ -76,51 +159,41 @@ struct Game {
     picks: Vec<Pick>,
 }
 
-fn parse_game(s: &str) -> Game {
-    let re = Regex::new(r"Game (\d+): (.*)").unwrap();
-    match re.captures(s) {
-        Some(caps) => {
-            let cubes_concat = caps.get(2).unwrap().as_str();
-            let picks = cubes_concat.split("; ").fold(Vec::<Pick>::new(), |acc, cc| {
-                let cubes: Vec<_> = cc.split(", ").fold(Vec::<(String, u32)>::new(), |acc, c| {
-                    let parts: Vec<_> = c.split(" ").collect();
-                    let count = parts.get(0).unwrap().parse::<u32>().unwrap();
-                    let color = parts.get(1).unwrap();
-                    let mut new_acc = acc.clone();
-                    new_acc.push((color.to_string(), count));
-                    new_acc
-                });
-                
-                let mut new_acc = acc.clone();
-                new_acc.push(Pick { cubes });
-                new_acc
-            });
-
-            Game {
-                id: caps.get(1).unwrap().as_str().parse::<u32>().unwrap(),
-                picks,
-            }
-        }
-        None => panic!("Cannot parse game from {}", s),
-    }
+/// A count and a colour, e.g. "3 blue".
+fn cube<'a>() -> impl Parser<'a, (String, u32)> {
+    map(pair(terminated(unsigned::<u32>(), space1()), word()), |(count, color)| (color.to_string(), count))
+}
+
+fn parse_game(s: &str) -> Result<Game, ParseError> {
+    let pick = map(separated_list1(cube(), tag(", ")), |cubes| Pick { cubes });
+    let game = labelled(preceded(tag("Game "), unsigned::<u32>()), separated_list1(pick, tag("; ")));
+    let (id, picks) = parse(game, s)?;
+    Ok(Game { id, picks })
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2, parse_game, Pick};
+    use crate::{part1, part1_with_bag, part2, parse_game, Bag, Pick, Violation};
     use anyhow::Result;
     use util::Input;
 
     #[test]
     pub fn test_parse_game_id() -> Result<()> {
-        let game = parse_game("Game 10: 3 blue, 4 red");
+        let game = parse_game("Game 10: 3 blue, 4 red")?;
         assert_eq!(game.id, 10);
         Ok(())
     }
 
+    #[test]
+    pub fn test_parse_game_error() -> Result<()> {
+        let err = parse_game("Game 10: 3 blue, four red").unwrap_err();
+        assert_eq!(err.position, 17);
+        Ok(())
+    }
+
     #[test]
     pub fn test_parse_pick() -> Result<()> {
-        let game = parse_game("Game 10: 3 blue, 4 red");
+        let game = parse_game("Game 10: 3 blue, 4 red")?;
         assert_eq!(game.picks, vec![
             Pick {
                 cubes: vec![
@@ -134,7 +207,7 @@ mod test {
 
     #[test]
     pub fn test_parse_multiple_pick() -> Result<()> {
-        let game = parse_game("Game 10: 3 blue, 4 red; 2 yellow, 1 green");
+        let game = parse_game("Game 10: 3 blue, 4 red; 2 yellow, 1 green")?;
         assert_eq!(game.picks, vec![
             Pick {
                 cubes: vec![
@@ -191,4 +264,44 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 60948);
         Ok(())
     }
+
+    #[test]
+    pub fn test_bag() -> Result<()> {
+        let bag: Bag = "12 red, 13 green, 14 blue".parse()?;
+        assert_eq!(bag, Bag::puzzle());
+        assert_eq!(bag.to_string(), "14 blue, 13 green, 12 red");
+        assert!("12 red, lots of blue".parse::<Bag>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_minimal_bag() -> Result<()> {
+        let game = parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green")?;
+        assert_eq!(game.minimal_bag(), "4 red, 2 green, 6 blue".parse()?);
+        assert_eq!(game.minimal_bag().power(), 48);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_impossible_picks() -> Result<()> {
+        let game = parse_game("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red, 2 purple")?;
+        assert_eq!(game.impossible_picks(&Bag::puzzle()), vec![
+            Violation { pick: 1, color: String::from("red"), count: 20, available: 12 },
+            Violation { pick: 3, color: String::from("purple"), count: 2, available: 0 },
+        ]);
+        assert_eq!(game.impossible_picks(&Bag::puzzle())[0].to_string(), "pick 1 shows 20 red, but the bag only has 12");
+        assert!(game.impossible_picks(&"20 red, 13 green, 6 blue, 2 purple".parse()?).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_part1_with_bag() -> Result<()> {
+        let input = Input::from_lines([
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+        ]);
+        assert_eq!(part1_with_bag(&input, &"4 red, 3 green, 6 blue".parse()?)?, 1 + 2);
+        Ok(())
+    }
 }
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::collections::{HashSet};
-use util::{Input, group_by};
+use std::collections::HashMap;
+use util::Input;
+use util::scan::Overflow;
 
 struct Grid {
     lines: Vec<String>,
@@ -25,13 +26,6 @@ fn to_grid(input: &Input) -> Grid {
     return Grid { lines, row_count, col_count }
 }
 
-fn char_at(grid: &Grid, coord: &Coord) -> char {
-    match grid.lines.get(coord.0).map(|s| s.chars().skip(coord.1).next()).flatten() {
-        Some(ch) => ch,
-        None => panic!("Failed to get char at coordinate {:?}", coord)
-    }
-}
-
 fn is_symbol(ch: char) -> bool {
     ch != '.' && !ch.is_digit(10)
 }
@@ -55,93 +49,113 @@ fn surrounding_coords(grid: &Grid, coord: &Coord) -> Vec<Coord> {
     vec
 }
 
-fn all_coords<'a>(grid: &'a Grid) -> impl Iterator<Item = Coord> + 'a {
-    (0..grid.row_count).flat_map(|r| (0..grid.col_count).map(move |c| Coord(r, c)))
+/// A number in the schematic, taking up `len` cells along its row from `start`.
+#[derive(Debug, PartialEq, Eq)]
+struct PartNumber {
+    value: u32,
+    start: Coord,
+    len: usize,
 }
 
-fn all_digit_coords<'a>(grid: &'a Grid) -> impl Iterator<Item = Coord> + 'a {
-    all_coords(grid).filter(|c| char_at(grid, c).is_digit(10))
+#[derive(Debug, PartialEq, Eq)]
+struct Symbol {
+    ch: char,
+    pos: Coord,
 }
 
-fn find_number_runs(grid: &Grid) -> Vec<Vec<Coord>> {
-    let mut runs: Vec<Vec<Coord>> = Vec::new();
-    let mut current_run: Option<Vec<Coord>> = None;
-    for coord in all_digit_coords(grid) {
-        match current_run {
-            // If we have a current run, and the digit in question immediately succeeds the last digit in that run,
-            // then add it to the run.
-            Some(ref mut run) if coord.1 > 0 && run.last().unwrap().1 == coord.1 - 1 => run.push(coord),
-            _ => {
-                // If we have a current run, stash it so we can begin a new one.
-                if let Some(ref grp) = current_run {
-                    // Stash the current group
-                    runs.push(grp.to_vec());
+/// All numbers and symbols of a schematic, with an index of which ones are adjacent. A number
+/// counts once per symbol even if several of its digits touch it.
+struct Schematic {
+    numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+    /// For each number, the indices of the symbols adjacent to it.
+    symbols_of_number: Vec<Vec<usize>>,
+    /// For each symbol, the indices of the numbers adjacent to it.
+    numbers_of_symbol: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    fn from_grid(grid: &Grid) -> Result<Schematic> {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        for (r, line) in grid.lines.iter().enumerate() {
+            let mut chars = line.chars().enumerate().peekable();
+            while let Some((c, ch)) = chars.next() {
+                if let Some(digit) = ch.to_digit(10) {
+                    // Keep consuming digits after an overflow, so the error shows the whole number.
+                    let (mut value, mut len) = (Some(digit), 1);
+                    while let Some(d) = chars.peek().and_then(|(_, ch)| ch.to_digit(10)) {
+                        value = value.and_then(|v| v.checked_mul(10)).and_then(|v| v.checked_add(d));
+                        len += 1;
+                        chars.next();
+                    }
+                    let value = value.ok_or_else(|| Overflow { text: line.chars().skip(c).take(len).collect(), type_name: "u32" })?;
+                    numbers.push(PartNumber { value, start: Coord(r, c), len });
+                } else if is_symbol(ch) {
+                    symbols.push(Symbol { ch, pos: Coord(r, c) });
                 }
-                // Begin a new run.
-                current_run = Some(vec![coord]);
             }
         }
-    }
 
-    // Stash final run, if any.
-    if let Some(ref grp) = current_run {
-        runs.push(grp.to_vec());
-    }
-
-    runs
-}
-
-fn run_to_number(grid: &Grid, run: &Vec<Coord>) -> u32 {
-    run.iter().fold(0, |acc, c| 10 * acc + char_at(&grid, c).to_digit(10).unwrap())
-}
-
-fn part1(input: &Input) -> Result<u32> {
-    let grid = to_grid(input);
+        let symbol_at: HashMap<&Coord, usize> = symbols.iter().enumerate().map(|(idx, s)| (&s.pos, idx)).collect();
+        let symbols_of_number = numbers.iter().map(|n| {
+            (0..n.len)
+                .flat_map(|offset| surrounding_coords(grid, &Coord(n.start.0, n.start.1 + offset)))
+                .filter_map(|c| symbol_at.get(&c).copied())
+                .unique()
+                .sorted()
+                .collect_vec()
+        }).collect_vec();
 
-    let sum: u32 = find_number_runs(&grid)
-        .iter()
-        .filter_map(|run| {
-            let surrounding: Vec<_> = run.iter().flat_map(|c| surrounding_coords(&grid, c)).unique().collect();
-            let first_symbol = surrounding.iter().find(|c| is_symbol(char_at(&grid, c)));
-            let number = run_to_number(&grid, run);
+        let mut numbers_of_symbol = vec![Vec::new(); symbols.len()];
+        for (number_idx, adjacent) in symbols_of_number.iter().enumerate() {
+            for symbol_idx in adjacent.iter() {
+                numbers_of_symbol[*symbol_idx].push(number_idx);
+            }
+        }
 
-            first_symbol.map(|_| number)
-        })
-        .sum();
+        Ok(Schematic { numbers, symbols, symbols_of_number, numbers_of_symbol })
+    }
 
-    Ok(sum)
-}
+    /// The numbers adjacent to at least one symbol in the set.
+    fn numbers_adjacent_to<'a>(&'a self, in_set: impl Fn(char) -> bool + 'a) -> impl Iterator<Item = &'a PartNumber> + 'a {
+        self.numbers.iter().zip(self.symbols_of_number.iter())
+            .filter(move |(_, adjacent)| adjacent.iter().any(|s| in_set(self.symbols[*s].ch)))
+            .map(|(n, _)| n)
+    }
 
-fn part2(input: &Input) -> Result<u32> {
-    let grid = to_grid(input);
-    let gear_coords: HashSet<_> = all_coords(&grid).filter(|c| char_at(&grid, c) == '*').collect();
+    /// The indices of the symbols in the set whose number of adjacent numbers satisfies the arity
+    /// check, e.g. `|n| n == 2` for exactly two or `|n| n >= 2` for at least two.
+    fn symbols_where<'a>(&'a self, in_set: impl Fn(char) -> bool + 'a, arity: impl Fn(usize) -> bool + 'a) -> impl Iterator<Item = usize> + 'a {
+        (0..self.symbols.len()).filter(move |s| in_set(self.symbols[*s].ch) && arity(self.numbers_of_symbol[*s].len()))
+    }
 
-    let numbers_with_gear_coord: Vec<_> = find_number_runs(&grid)
-        .iter()
-        .filter_map(|run| {
-            let surrounding: Vec<_> = run.iter().flat_map(|c| surrounding_coords(&grid, c)).unique().collect();
-            let gear_coord = surrounding.iter().find(|c| gear_coords.contains(c));
-            let number = run_to_number(&grid, run);
+    fn adjacent_values(&self, symbol: usize) -> impl Iterator<Item = u64> + '_ {
+        self.numbers_of_symbol[symbol].iter().map(|n| self.numbers[*n].value as u64)
+    }
 
-            gear_coord.map(|c| (c.clone(), number))
-        })
-        .collect();
+    fn product(&self, symbol: usize) -> u64 {
+        self.adjacent_values(symbol).product()
+    }
 
-    // Group by coord
-    let groups = group_by(&numbers_with_gear_coord, |tup| &tup.0, |tup| &tup.1);
+    fn sum(&self, symbol: usize) -> u64 {
+        self.adjacent_values(symbol).sum()
+    }
+}
 
-    let sum: u32 = groups
-        .iter()
-        .filter(|e| e.1.len() == 2) // limit to groups with exactly two numbers
-        .map(|e| e.1.iter().fold(1, |acc, x| acc * *x)) // multiply the numbers
-        .sum();
+fn part1(input: &Input) -> Result<u32> {
+    let schematic = Schematic::from_grid(&to_grid(input))?;
+    Ok(schematic.numbers_adjacent_to(|_| true).map(|n| n.value).sum())
+}
 
-    Ok(sum)
+fn part2(input: &Input) -> Result<u64> {
+    let schematic = Schematic::from_grid(&to_grid(input))?;
+    Ok(schematic.symbols_where(|ch| ch == '*', |n| n == 2).map(|s| schematic.product(s)).sum())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2, surrounding_coords, to_grid, Coord};
+    use crate::{part1, part2, surrounding_coords, to_grid, Coord, PartNumber, Schematic};
     use anyhow::Result;
     use util::Input;
 
@@ -219,4 +233,43 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 79026871);
         Ok(())
     }
+
+    #[test]
+    pub fn test_schematic_queries() -> Result<()> {
+        let input = Input::from_lines([
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ]);
+        let schematic = Schematic::from_grid(&to_grid(&input))?;
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.numbers[0], PartNumber { value: 467, start: Coord(0, 0), len: 3 });
+        assert_eq!(schematic.symbols.len(), 6);
+
+        let by_hash = schematic.numbers_adjacent_to(|ch| ch == '#').map(|n| n.value).collect::<Vec<_>>();
+        assert_eq!(by_hash, vec![633]);
+        let not_adjacent = schematic.numbers.iter().filter(|n| !schematic.numbers_adjacent_to(|_| true).any(|m| m == *n)).map(|n| n.value).collect::<Vec<_>>();
+        assert_eq!(not_adjacent, vec![114, 58]);
+
+        let lonely_gear = schematic.symbols_where(|ch| ch == '*', |n| n == 1).collect::<Vec<_>>();
+        assert_eq!(lonely_gear.len(), 1);
+        assert_eq!(schematic.sum(lonely_gear[0]), 617);
+        assert_eq!(schematic.symbols_where(|_| true, |n| n >= 2).map(|s| schematic.sum(s)).collect::<Vec<_>>(), vec![467 + 35, 755 + 598]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_number_overflow() -> Result<()> {
+        assert_eq!(part1(&Input::from_lines(["4294967295*"]))?, u32::MAX);
+        let error = part1(&Input::from_lines([".42949672960*"])).unwrap_err();
+        assert_eq!(error.to_string(), "42949672960 doesn't fit in u32");
+        Ok(())
+    }
 }
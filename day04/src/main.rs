@@ -1,30 +1,52 @@
-use anyhow::Result;
-use std::collections::{HashSet, HashMap};
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
 
-use util::Input;
+use util::{FromInput, Input};
 
+#[derive(FromInput)]
+#[input("Card {id}: {winning: ws} | {on_hand: ws}")]
 struct Card {
     id: u32,
     winning: HashSet<u32>,
     on_hand: Vec<u32>,
 }
 
-fn to_card(line: &str) -> Card {
-    let col_idx = line.find(":").unwrap();
-    let pipe_idx = line.find("|").unwrap();
-    let winning: HashSet<_> = line[col_idx+1..pipe_idx].trim().split_ascii_whitespace().map(|str_num| str_num.parse::<u32>().unwrap()).collect();
-    let on_hand: Vec<_> = line[pipe_idx+1..].trim().split_ascii_whitespace().map(|str_num| str_num.parse::<u32>().unwrap()).collect();
-    let id = line[..col_idx].replace("Card", "").trim().parse::<u32>().unwrap();
+impl Card {
+    /// The number of winning numbers on hand.
+    fn matches(&self) -> usize {
+        self.on_hand.iter().filter(|num| self.winning.contains(num)).count()
+    }
+}
+
+/// The final number of copies of each card, indexed by position, in a single pass: a card's count
+/// is final once all cards before it have been processed. Cards must be numbered 1, 2, 3, ... in
+/// order, and no card may win copies of cards past the end of the table.
+fn copy_counts(cards: &[Card]) -> Result<Vec<u32>> {
+    for (idx, card) in cards.iter().enumerate() {
+        if card.id as usize != idx + 1 {
+            return Err(anyhow!("Card number {} at line {}, expected card {}", card.id, idx + 1, idx + 1));
+        }
+    }
 
-    return Card { id, winning, on_hand };
+    let mut counts = vec![1u32; cards.len()];
+    for (idx, card) in cards.iter().enumerate() {
+        let wins = card.matches();
+        if idx + wins >= cards.len() {
+            return Err(anyhow!("Card {} wins copies up to card {}, but the last card is {}", card.id, idx + 1 + wins, cards.len()));
+        }
+        let copies = counts[idx];
+        for count in counts[idx + 1..=idx + wins].iter_mut() {
+            *count += copies;
+        }
+    }
+    Ok(counts)
 }
 
 fn part1(input: &Input) -> Result<u32> {
-    let result: u32 = input.as_lines()
-        .map(|line| {
-            let card = to_card(line);
-
-            let wins = card.on_hand.iter().filter(|num| card.winning.contains(num)).count() as u32;
+    let cards: Vec<Card> = input.parse_lines()?;
+    let result: u32 = cards.iter()
+        .map(|card| {
+            let wins = card.matches() as u32;
             let points = if wins > 0 { 1 << (wins-1) } else { 0 };
 
             points
@@ -34,30 +56,27 @@ fn part1(input: &Input) -> Result<u32> {
 }
 
 fn part2(input: &Input) -> Result<u32> {
-    let cards: Vec<_> = input.as_lines().map(|line| to_card(line)).collect();
-
-    // Create HashMap with initial counts
-    let mut card_counts: HashMap<_, _> = cards.iter().map(|c| (c.id, 1)).collect();
-
-    for card in &cards {
-        let winning_card_count = card_counts.get(&card.id).unwrap().clone();
-        let win_count = card.on_hand.iter().filter(|num| card.winning.contains(num)).count() as u32;
-        let next = card.id + 1;
-        for following_card_id in next..(next + win_count) {
-            card_counts.entry(following_card_id).and_modify(|c| *c += winning_card_count);
-        }
-    }
-
-    let result = card_counts.values().sum();
-    Ok(result)
+    let cards: Vec<Card> = input.parse_lines()?;
+    Ok(copy_counts(&cards)?.iter().sum())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2};
+    use crate::{copy_counts, part1, part2, Card};
     use anyhow::Result;
     use util::Input;
 
+    #[test]
+    pub fn test_parse_error() -> Result<()> {
+        let input = Input::from_lines([
+            "Card 1: 41 48 | 83 86",
+            "Card 2: 13 3x | 61 30",
+        ]);
+        let err = input.parse_lines::<Card>().err().unwrap();
+        assert_eq!(err.to_string(), "line 2: expected an item of `winning` at position 11, found \"3x | 61 30\"");
+        Ok(())
+    }
+
     #[test]
     pub fn test_part1() -> Result<()> {
         let input = Input::from_lines([
@@ -99,4 +118,36 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 5329815);
         Ok(())
     }
+
+    #[test]
+    pub fn test_copy_counts() -> Result<()> {
+        let input = Input::from_lines([
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]);
+        assert_eq!(copy_counts(&input.parse_lines::<Card>()?)?, vec![1, 2, 4, 8, 14, 1]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_copy_count_errors() -> Result<()> {
+        let out_of_order = Input::from_lines([
+            "Card 1: 41 | 83",
+            "Card 3: 13 | 61",
+        ]);
+        let err = copy_counts(&out_of_order.parse_lines::<Card>()?).err().unwrap();
+        assert_eq!(err.to_string(), "Card number 3 at line 2, expected card 2");
+
+        let past_end = Input::from_lines([
+            "Card 1: 41 48 | 41 48",
+            "Card 2: 13 32 | 61 30",
+        ]);
+        let err = copy_counts(&past_end.parse_lines::<Card>()?).err().unwrap();
+        assert_eq!(err.to_string(), "Card 1 wins copies up to card 3, but the last card is 2");
+        Ok(())
+    }
 }
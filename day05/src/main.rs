@@ -64,12 +64,9 @@ fn parse_map_label(line: &str) -> (ComponentKind, ComponentKind) {
 }
 
 fn parse_almanac(input: &Input) -> Almanac {
-    let lines_vec = input.as_lines().collect_vec();
-    let chunks = lines_vec.split(|line| *line == "").map(|chunk| chunk.to_vec()).collect_vec();
-
     let a = Almanac { seeds: vec![], maps: vec![] };
-    chunks.iter().fold(a, |almanac, chunk| {
-        let first_line = *chunk.first().unwrap();
+    input.blocks().iter().fold(a, |almanac, block| {
+        let first_line = block.as_lines().next().unwrap();
         if first_line.starts_with("seeds:") {
             // Parse the seeds line by splitting the numbers.
             let col_idx = first_line.find(":").unwrap();
@@ -82,8 +79,8 @@ fn parse_almanac(input: &Input) -> Almanac {
             // Find the components of the map line.
             let components = parse_map_label(first_line);
             // Read each line to get a single mapping.
-            let parts = chunk.iter().skip(1).map(|line| {
-                let nums = line.split_ascii_whitespace().map(|s| s.parse::<u64>().unwrap()).collect_vec();
+            let parts = block.numbered_lines().skip(1).map(|(line_no, line)| {
+                let nums = line.split_ascii_whitespace().map(|s| s.parse::<u64>().unwrap_or_else(|_| panic!("Line {}: bad number '{}'", line_no, s))).collect_vec();
                 MapPart {
                     dest_range_start: *nums.get(0).unwrap(),
                     source_range_start: *nums.get(1).unwrap(),
@@ -148,7 +145,8 @@ fn part2(input: &Input) -> Result<u64> {
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2, parse_almanac, Map, MapPart, Almanac, ComponentKind};
+    use input_gen::day05::generate;
+    use crate::{part1, parse_almanac, Map, MapPart, Almanac, ComponentKind};
     use anyhow::Result;
     use util::Input;
 
@@ -223,4 +221,12 @@ mod test {
     //     assert_eq!(part2(&input).unwrap(), 0);
     //     Ok(())
     // }
+
+    #[test]
+    pub fn test_generate_at_least_one_seed() -> Result<()> {
+        let input = Input::normalize(generate(0, 1));
+        assert_eq!(input.as_lines().next().unwrap().split_whitespace().count(), 3);
+        part1(&input)?;
+        Ok(())
+    }
 }
@@ -1,83 +1,111 @@
 use anyhow::{anyhow, Result};
 use itertools::Itertools;
 
-use util::Input;
+use util::{Input, numbers};
 
 struct Race {
     time: u64,
     distance: u64,
 }
 
-fn extract_numbers(s: &str) -> Vec<u64> {
-    s
-        .find(":")
-        .iter()
-        .flat_map(|col_idx| s.get((col_idx+1)..))
-        .flat_map(|ns| ns.trim().split_ascii_whitespace())
-        .map(|ns| ns.parse::<u64>().unwrap())
-        .collect_vec()
+/// Splits off the time and distance lines, each a list of numbers after a label.
+fn time_and_distance_lines<'a>(mut lines: impl Iterator<Item = &'a [u8]>) -> Result<(&'a [u8], &'a [u8])> {
+    match (lines.next(), lines.next()) {
+        (Some(times), Some(distances)) => Ok((times, distances)),
+        _ => Err(anyhow!("Expected a line of times and a line of distances")),
+    }
 }
 
-fn remove_whitespace(s: &str) -> String {
-    s.chars().filter(|c| !c.is_whitespace()).collect()
+/// All the digits on the line as one number, ignoring the spaces between them.
+fn joined_number(line: &[u8]) -> Result<u64> {
+    line.iter().filter(|b| b.is_ascii_digit()).try_fold(0u64, |acc, b| {
+        acc.checked_mul(10)
+            .and_then(|acc| acc.checked_add((b - b'0') as u64))
+            .ok_or_else(|| anyhow!("Number on {:?} doesn't fit in u64", String::from_utf8_lossy(line)))
+    })
 }
 
 impl Race {
-    fn from_lines(lines: Vec<String>) -> Vec<Race> {
-        let mut iter = lines.iter();
-        let first_opt = iter.next();
-        let second_opt = iter.next();
-
-        match (first_opt, second_opt) {
-            (Some(first), Some(second)) => {
-                let times = extract_numbers(first);
-                let distances = extract_numbers(second);
-                times.iter().zip(distances.iter())
-                    .map(|(time, distance)| Race { time: *time, distance: *distance })
-                    .collect_vec()
-            }
-            _ => panic!("Malformed input"),
-        }
-    }
-
-    fn from_input(input: &Input) -> Vec<Race> {
-        Race::from_lines(input.as_lines().map(|s| s.to_string()).collect_vec())
+    fn from_input(input: &Input) -> Result<Vec<Race>> {
+        let (times, distances) = time_and_distance_lines(input.lines_bytes())?;
+        let times = numbers::<u64>(times).collect::<Result<Vec<_>, _>>()?;
+        let distances = numbers::<u64>(distances).collect::<Result<Vec<_>, _>>()?;
+        Ok(times.into_iter().zip(distances).map(|(time, distance)| Race { time, distance }).collect_vec())
     }
 
-    fn from_input_p2(input: &Input) -> Vec<Race> {
-        let lines = input.as_lines()
-            .map(|line| remove_whitespace(line))
-            .collect_vec();
-        Race::from_lines(lines)
+    fn from_input_p2(input: &Input) -> Result<Race> {
+        let (times, distances) = time_and_distance_lines(input.lines_bytes())?;
+        Ok(Race { time: joined_number(times)?, distance: joined_number(distances)? })
     }
 
-    fn distance_from_hold_time(&self, ht: u64) -> u64 {
-        if ht >= self.time { 0 } else { (self.time - ht) * ht }
+    /// Whether holding the button for ht milliseconds beats the record distance.
+    fn wins(&self, ht: u64) -> bool {
+        ht < self.time && (self.time - ht) as u128 * ht as u128 > self.distance as u128
     }
 
-    fn win_count(&self) -> u32 {
-        (0..self.time).filter(|ht| self.distance_from_hold_time(*ht) > self.distance).count() as u32
+    /// Counts the winning hold times using the roots of ht * (time - ht) = distance. The square
+    /// root is an exact integer one, and the first winning hold time is nudged to absorb its
+    /// rounding. The winning hold times are symmetric around time / 2.
+    fn win_count(&self) -> u64 {
+        let (t, d) = (self.time as u128, self.distance as u128);
+        let Some(disc) = (t * t).checked_sub(4 * d) else { return 0 };
+        let mut first = ((t - disc.isqrt()) / 2) as u64;
+        while first > 0 && self.wins(first - 1) {
+            first -= 1;
+        }
+        while first <= self.time / 2 && !self.wins(first) {
+            first += 1;
+        }
+        if first > self.time / 2 { 0 } else { self.time - 2 * first + 1 }
     }
-
 }
 
-fn part1(input: &Input) -> Result<u32> {
-    let races = Race::from_input(input);
+fn part1(input: &Input) -> Result<u64> {
+    let races = Race::from_input(input)?;
     let res = races.iter().fold(1, |acc, race| acc * race.win_count());
     Ok(res)
 }
 
-fn part2(input: &Input) -> Result<u32> {
-    let races = Race::from_input_p2(input);
-
-    races.first().map(|r| r.win_count()).ok_or_else(|| anyhow!("No first race"))
+fn part2(input: &Input) -> Result<u64> {
+    Ok(Race::from_input_p2(input)?.win_count())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2};
+    use input_gen::day06::generate;
+    use crate::{part1, part2, Race};
     use anyhow::Result;
     use util::Input;
+    use util::rng::Rng;
+
+    fn brute_force(race: &Race) -> u64 {
+        (0..=race.time).filter(|ht| race.wins(*ht)).count() as u64
+    }
+
+    #[test]
+    pub fn test_win_count_against_brute_force() {
+        for (time, distance) in [(7, 9), (15, 40), (30, 200), (71530, 940200), (0, 0), (4, 3), (4, 4)] {
+            let race = Race { time, distance };
+            assert_eq!(race.win_count(), brute_force(&race), "time {} distance {}", time, distance);
+        }
+
+        let mut rng = Rng::new(1);
+        for _ in 0..2000 {
+            let time = rng.below(500);
+            let distance = rng.below(time * time / 4 + 10);
+            let race = Race { time, distance };
+            assert_eq!(race.win_count(), brute_force(&race), "time {} distance {}", time, distance);
+        }
+    }
+
+    #[test]
+    pub fn test_huge_race() {
+        // Far too long to scan: the record is one short of the best distance, so only the
+        // middle hold time wins.
+        let time = 4_000_000_000u64;
+        let race = Race { time, distance: (time / 2) * (time / 2) - 1 };
+        assert_eq!(race.win_count(), 1);
+    }
 
     #[test]
     pub fn test_part1() -> Result<()> {
@@ -112,4 +140,22 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 23632299);
         Ok(())
     }
+
+    #[test]
+    pub fn test_overflow() {
+        let input = Input::from_lines(["Time: 99999 99999 99999 99999", "Distance: 1 1 1 1"]);
+        assert_eq!(part2(&input).unwrap_err().to_string(), "Number on \"Time: 99999 99999 99999 99999\" doesn't fit in u64");
+        let input = Input::from_lines(["Time: 99999999999999999999", "Distance: 1"]);
+        assert_eq!(part1(&input).unwrap_err().to_string(), "99999999999999999999 doesn't fit in u64");
+    }
+
+    #[test]
+    pub fn test_generate() -> Result<()> {
+        let input = Input::normalize(generate(4, 3));
+        assert_eq!(part1(&input)?, Race::from_input(&input)?.iter().map(brute_force).product::<u64>());
+        // Two races join into a race short enough to scan.
+        let input = Input::normalize(generate(2, 5));
+        assert_eq!(part2(&input)?, brute_force(&Race::from_input_p2(&input)?));
+        Ok(())
+    }
 }
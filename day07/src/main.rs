@@ -2,8 +2,10 @@ use anyhow::Result;
 use itertools::Itertools;
 use std::cmp::Ordering;
 
-use util::Input;
+use util::{FromInput, Input};
 
+#[derive(FromInput)]
+#[input("{hand} {bid}")]
 struct HandBid {
     hand: String,
     bid: u32
@@ -126,16 +128,8 @@ fn compare_hands_desc(a: &str, b: &str) -> Ordering {
     compare_hands_asc(a, b).reverse()
 }
 
-fn parse_input(input: &Input) -> Vec<HandBid> {
-    input.as_lines()
-        .map(|line| {
-            let parts = line.split_ascii_whitespace().collect_vec();
-            match parts[..] {
-                [a, b] => HandBid { hand: a.to_string(), bid: b.parse().unwrap() },
-                _ => panic!("Unexpected line: {}", line),
-            }
-        })
-        .collect_vec()
+fn parse_input(input: &Input) -> Result<Vec<HandBid>> {
+    Ok(input.parse_lines()?)
 }
 
 fn winnings(hand_bids: &Vec<HandBid>) -> u32 {
@@ -155,14 +149,14 @@ fn winnings(hand_bids: &Vec<HandBid>) -> u32 {
 
 fn part1(input: &Input) -> Result<u32> {
     // sort so that first is strongest
-    let hand_bids = parse_input(input);
+    let hand_bids = parse_input(input)?;
     let result = winnings(&hand_bids);
 
     Ok(result)
 }
 
 fn part2(input: &Input) -> Result<u32> {
-    let hand_bids = parse_input(input)
+    let hand_bids = parse_input(input)?
         .iter()
         .map(|hb| {
             let jokered = hb.hand.replace("J", "*");
@@ -1,102 +1,112 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
-use regex::Regex;
 
-use util::Input;
-use num_integer::lcm;
+use util::{Input, Interner};
+use util::math::lcm_all;
+use util::parse::{delimited, key_value, parse, tag, word, ParseError};
 use tailcall::tailcall;
 
-#[derive(Debug, Clone)]
-struct Node {
-    name: String,
-    left: String,
-    right: String,
-}
-
+/// The network as a dense graph: node names are interned, and each node id indexes its
+/// [left, right] successors.
 #[derive(Debug)]
 struct Map {
-    instructions: Vec<char>,
-    nodes: Vec<Node>,
+    /// 0 for left, 1 for right, so each instruction indexes directly into a node's successors.
+    instructions: Vec<usize>,
+    names: Interner,
+    nodes: Vec<[u32; 2]>,
 }
 
-fn parse_input(input: &Input) -> Map {
-    let lines = input.as_lines().collect_vec();
-    let instructions = lines.first().unwrap().chars().collect_vec();
-
-    let re = Regex::new(r"(?<node>[A-Z0-9]{3}) = \((?<left>[A-Z0-9]{3}), (?<right>[A-Z0-9]{3})\)").unwrap();
-
-    let nodes = lines.iter().skip(2).map(|line| {
-        match re.captures(line) {
-            Some(caps) => {
-                let name = caps["node"].to_string();
-                let left = caps["left"].to_string();
-                let right = caps["right"].to_string();
-                Node { name, left, right}
-            },
-            None => panic!("Unrecognized: {}", line),
-        }
-    }).collect_vec();
-
-    Map { instructions, nodes }
+/// Parses the L/R instruction line, which must not be empty.
+fn parse_instructions(line: &str) -> Result<Vec<usize>, ParseError> {
+    if let Some(position) = line.find(|ch| ch != 'L' && ch != 'R').or(line.is_empty().then_some(0)) {
+        let found = line[position..].chars().take(10).collect();
+        return Err(ParseError { line: None, position, expected: "L or R".to_string(), found });
+    }
+    Ok(line.chars().map(|ch| if ch == 'L' { 0 } else { 1 }).collect())
 }
 
-fn find_next_node_name<'a>(map: &'a Map, instr_idx: usize, node_name: &str) -> &'a str {
-    let lr = map.instructions.get(instr_idx).unwrap();
-    let node = map.nodes.iter().find(|n| n.name == node_name).unwrap();
+fn parse_input(input: &Input) -> Result<Map> {
+    let (header, body) = input.header_and_body().ok_or_else(|| anyhow!("Expected instructions, a blank line and nodes"))?;
+    let instructions = parse_instructions(header.as_str()).map_err(|e| e.at_line(header.start_line()))?;
+
+    let node_line = key_value(word(), " = ", delimited(tag("("), key_value(word(), ", ", word()), tag(")")));
+
+    let mut names = Interner::new();
+    let mut nodes: Vec<Option<[u32; 2]>> = Vec::new();
+    for (line_no, line) in body.numbered_lines() {
+        let (node, (left, right)) = parse(&node_line, line).map_err(|e| e.at_line(line_no))?;
+        let id = names.intern(node) as usize;
+        let left = names.intern(left);
+        let right = names.intern(right);
+        // Successors may be named before their own line, so grow with the interner.
+        nodes.resize(names.len(), None);
+        nodes[id] = Some([left, right]);
+    }
+    let nodes = nodes
+        .iter()
+        .enumerate()
+        .map(|(id, node)| node.ok_or_else(|| anyhow!("Node {} is used but never defined", names.name(id as u32))))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Map { instructions, names, nodes })
+}
 
-    if *lr == 'L' { &node.left } else { &node.right }
+fn find_next_node(map: &Map, instr_idx: usize, node: u32) -> u32 {
+    map.nodes[node as usize][map.instructions[instr_idx]]
 }
 
-fn steps(map: &Map) -> u32 {
+fn steps(map: &Map) -> Result<u32> {
 
     #[tailcall]
-    fn steps_inner(map: &Map, node_name: &str, idx: usize, step_count: u32) -> u32 {
-        match node_name {
-            "ZZZ" => step_count,
-            _ => {
-                let next_node_name = find_next_node_name(map, idx, node_name);
-                let next_idx = (idx + 1) % map.instructions.len();
-                steps_inner(map, next_node_name, next_idx, step_count + 1)
-            }
+    fn steps_inner(map: &Map, node: u32, end: u32, idx: usize, step_count: u32) -> u32 {
+        if node == end {
+            step_count
+        } else {
+            let next_node = find_next_node(map, idx, node);
+            let next_idx = (idx + 1) % map.instructions.len();
+            steps_inner(map, next_node, end, next_idx, step_count + 1)
         }
     }
 
-    steps_inner(map, "AAA", 0, 0)
+    let start = map.names.get("AAA").ok_or_else(|| anyhow!("No node AAA"))?;
+    let end = map.names.get("ZZZ").ok_or_else(|| anyhow!("No node ZZZ"))?;
+    Ok(steps_inner(map, start, end, 0, 0))
 }
 
-fn find_period(map: &Map, initial_node_name: &str) -> u32 {
+fn find_period(map: &Map, initial_node: u32) -> u32 {
+    let is_end: Vec<bool> = map.names.iter().map(|(_, name)| name.ends_with('Z')).collect();
 
     #[tailcall]
-    fn find_period_inner(map: &Map, node_name: &str, idx: usize, step_count: u32) -> u32 {
-        if node_name.ends_with("Z") {
+    fn find_period_inner(map: &Map, is_end: &[bool], node: u32, idx: usize, step_count: u32) -> u32 {
+        if is_end[node as usize] {
             step_count
         } else {
-            let next_node_name = find_next_node_name(map, idx, node_name);
+            let next_node = find_next_node(map, idx, node);
             let next_idx = (idx + 1) % map.instructions.len();
-            find_period_inner(map, next_node_name, next_idx, step_count + 1)
+            find_period_inner(map, is_end, next_node, next_idx, step_count + 1)
         }
     }
 
-    find_period_inner(map, initial_node_name, 0, 0)
+    find_period_inner(map, &is_end, initial_node, 0, 0)
 }
 
-fn steps_sim(map: &Map) -> u64 {
-    let node_names = map.nodes.iter().filter(|n| n.name.ends_with("A")).collect_vec();
+fn steps_sim(map: &Map) -> Result<u64> {
+    let start_nodes = map.names.iter().filter(|(_, name)| name.ends_with('A')).map(|(id, _)| id).collect_vec();
 
-    let periods = node_names.iter().map(|nn| find_period(map, &nn.name)).collect_vec();
+    let periods = start_nodes.iter().map(|node| find_period(map, *node)).collect_vec();
 
-    periods.iter().fold(1u64, |acc, p| lcm(acc, *p as u64))
+    lcm_all(&periods.iter().map(|p| *p as u64).collect_vec()).ok_or_else(|| anyhow!("Step count overflows u64"))
 }
 
 fn part1(input: &Input) -> Result<u32> {
-    let map = parse_input(input);
-    let result = steps(&map);
+    let map = parse_input(input)?;
+    let result = steps(&map)?;
     Ok(result)
 }
 
 fn part2(input: &Input) -> Result<u64> {
-    let map = parse_input(input);
-    let result = steps_sim(&map);
+    let map = parse_input(input)?;
+    let result = steps_sim(&map)?;
     Ok(result)
 }
 
@@ -140,4 +150,26 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 14935034899483u64);
         Ok(())
     }
+
+    #[test]
+    pub fn test_malformed_network() {
+        let error = |lines: &[&str]| part1(&Input::from_lines(lines)).unwrap_err().to_string();
+        assert_eq!(error(&["L", "", "AAA = (BBB, ZZZ)", "ZZZ = (ZZZ, ZZZ)"]), "Node BBB is used but never defined");
+        assert_eq!(error(&["L", "", "BBB = (ZZZ, ZZZ)", "ZZZ = (ZZZ, ZZZ)"]), "No node AAA");
+        assert_eq!(error(&["L", "", "AAA = (AAA, AAA)"]), "No node ZZZ");
+    }
+
+    #[test]
+    pub fn test_malformed_instructions() {
+        let error = |header: &str| part1(&Input::from_lines([header, "", "AAA = (ZZZ, ZZZ)", "ZZZ = (ZZZ, ZZZ)"])).unwrap_err().to_string();
+        assert_eq!(error("LRx"), "line 1: expected L or R at position 2, found \"x\"");
+        assert_eq!(error("LR l"), "line 1: expected L or R at position 2, found \" l\"");
+        assert_eq!(error(""), "line 1: expected L or R at position 0, found end of input");
+    }
+
+    #[test]
+    pub fn test_malformed_node_line() {
+        let error = part1(&Input::from_lines(["L", "", "AAA = (ZZZ, ZZZ)", "ZZZ = ZZZ, ZZZ"])).unwrap_err().to_string();
+        assert_eq!(error, "line 4: expected \"(\" at position 6, found \"ZZZ, ZZZ\"");
+    }
 }
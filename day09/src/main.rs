@@ -1,46 +1,33 @@
-use anyhow::Result;
-use itertools::Itertools;
+use anyhow::{anyhow, Result};
 
-use util::Input;
+use util::{Input, numbers};
+use util::poly::Polynomial;
 
-fn parse_line(line: &str) -> Vec<i32> {
-    let v = line.split_ascii_whitespace().map(|s| s.parse::<i32>().unwrap()).collect_vec();
-    v
+fn parse_line(line: &[u8]) -> Result<Vec<i64>> {
+    Ok(numbers::<i64>(line).collect::<Result<Vec<_>, _>>()?)
 }
 
-fn find_diffs(nums: &Vec<i32>) -> Vec<i32> {
-    let v = nums.iter().zip(nums.iter().skip(1)).map(|(a, b)| b - a).collect_vec();
-    v
+/// Sums the values of each line's polynomial at the index given relative to the line's length.
+fn sum_extrapolated(input: &Input, index: impl Fn(usize) -> i64) -> Result<i64> {
+    let sum = input.lines_bytes().map(parse_line).enumerate().try_fold(0i128, |acc, (idx, values)| {
+        let values = values?;
+        if values.is_empty() {
+            return Err(anyhow!("Line {}: expected a sequence of numbers", idx + 1));
+        }
+        let value = Polynomial::from_values(&values)
+            .and_then(|p| p.eval(index(values.len())))
+            .ok_or_else(|| anyhow!("Overflow extrapolating {:?}", values))?;
+        acc.checked_add(value).ok_or_else(|| anyhow!("Overflow in sum"))
+    })?;
+    i64::try_from(sum).map_err(|_| anyhow!("Sum {} doesn't fit in an i64", sum))
 }
 
-fn next_num(nums: &Vec<i32>) -> i32 {
-    let diffs = find_diffs(nums);
-    if diffs.iter().all(|n| *n == 0) {
-        *nums.last().unwrap()
-    } else {
-        nums.last().unwrap() + next_num(&diffs)
-    }
+fn part1(input: &Input) -> Result<i64> {
+    sum_extrapolated(input, |len| len as i64)
 }
 
-fn prev_num(nums: &Vec<i32>) -> i32 {
-    let diffs = find_diffs(nums);
-    if diffs.iter().all(|n| *n == 0) {
-        *nums.first().unwrap()
-    } else {
-        nums.first().unwrap() - prev_num(&diffs)
-    }
-}
-
-fn part1(input: &Input) -> Result<i32> {
-    let vecs = input.as_lines().map(|line| parse_line(line)).collect_vec();
-    let res: i32 = vecs.iter().map(|v| next_num(v)).sum();
-    Ok(res)
-}
-
-fn part2(input: &Input) -> Result<i32> {
-    let vecs = input.as_lines().map(|line| parse_line(line)).collect_vec();
-    let res: i32 = vecs.iter().map(|v| prev_num(v)).sum();
-    Ok(res)
+fn part2(input: &Input) -> Result<i64> {
+    sum_extrapolated(input, |_| -1)
 }
 
 #[cfg(test)]
@@ -93,4 +80,10 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 803);
         Ok(())
     }
+
+    #[test]
+    pub fn test_empty_line() {
+        let input = Input::from_lines(["0 3 6", "", "1 3 6"]);
+        assert_eq!(part1(&input).unwrap_err().to_string(), "Line 2: expected a sequence of numbers");
+    }
 }
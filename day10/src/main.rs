@@ -1,9 +1,38 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use anyhow::Result;
+use std::io::{BufRead, Write};
+
+use anyhow::{anyhow, Result};
 
 use itertools::Itertools;
 use util::Input;
+use util::viz::{Animator, Color, Frame, Pace};
+use util::viz::svg::{render_path_from_args, CellStyle, Svg};
+
+/// Usage: day10 [--render <out.svg>] [--animate] [--delay <ms> | --step]
+///
+/// --render writes a picture of the loop. With any of the other flags the walk along the loop
+/// from S is animated instead of solving the puzzle.
+fn main() -> Result<()> {
+    let input = Input::load("day10/input")?;
+    let mut args = std::env::args().skip(1).collect_vec();
+
+    if let Some(path) = render_path_from_args(&mut args).map_err(|e| anyhow!(e))? {
+        render(&input).write(&path)?;
+        println!("Wrote {}", path.display());
+    }
+
+    if let Some(pace) = Pace::from_args(args).map_err(|e| anyhow!(e))? {
+        return animate(&input, &mut Animator::terminal(pace));
+    }
+
+    println!("Part 1:");
+    println!("{}", part1(&input)?);
+
+    println!("Part 2:");
+    println!("{}", part2(&input)?);
+    Ok(())
+}
 
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
 struct Coordinate {
@@ -74,20 +103,17 @@ fn find_pipe_coordinates(map: &Map) -> Vec<Coordinate> {
     let mut current = s.1; // arbitrary, could be s.2 as well
 
     while let Some(tile) = map.coordinates.get(&current) {
-        match tile {
-            'S' => break, // done
-            _ => {
-                let coords_from = coordinates_from(tile, &current);
-                let next = coords_from.iter().find(|cc| **cc != from);
-                match next {
-                    Some(n) => {
-                        from = current.clone(); // TODO: get rid of clone
-                        current = n.clone();
-                        pipe.push(n.clone());
-                    },
-                    None => panic!("No next coordinate"),
-                }
-            }
+        if *tile == 'S' {
+            break; // done
+        }
+        pipe.push(current.clone());
+        let coords_from = coordinates_from(tile, &current);
+        match coords_from.into_iter().find(|cc| *cc != from) {
+            Some(next) => {
+                from = current;
+                current = next;
+            },
+            None => panic!("No next coordinate"),
         }
     }
 
@@ -99,6 +125,58 @@ fn find_max_dist(map: &Map) -> usize {
     pipe.len() / 2
 }
 
+/// The pipe tile drawn with box-drawing characters.
+fn box_char(tile: char) -> char {
+    match tile {
+        '|' => '│',
+        '-' => '─',
+        'F' => '┌',
+        '7' => '┐',
+        'L' => '└',
+        'J' => '┘',
+        other => other,
+    }
+}
+
+/// Walks the loop from S, drawing each pipe tile as it is reached.
+fn animate<W: Write, R: BufRead>(input: &Input, animator: &mut Animator<W, R>) -> Result<()> {
+    let map = to_map(input);
+    let pipe = find_pipe_coordinates(&map);
+    let mut frame = Frame::from_text(input.as_str(), |_| Color::Gray);
+    for (step, coord) in pipe.iter().enumerate() {
+        let tile = map.coordinates[coord];
+        let color = if tile == 'S' { Color::Yellow } else { Color::Green };
+        frame.set((coord.x as usize, coord.y as usize), box_char(tile), color);
+        let caption = format!("Step {} of {}", step, pipe.len() - 1);
+        if !animator.show(&frame.clone().with_caption(caption))? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Draws the loop as a closed line over the tiles, with the loop's tiles lit up and S marked.
+fn render(input: &Input) -> Svg {
+    let map = to_map(input);
+    let pipe = find_pipe_coordinates(&map);
+    let on_loop: HashSet<&Coordinate> = pipe.iter().collect();
+    let lines = input.as_lines().collect_vec();
+    let points = pipe.iter().map(|c| (c.x as usize, c.y as usize)).collect_vec();
+
+    let mut svg = Svg::new(lines.first().map(|l| l.len()).unwrap_or(0), lines.len());
+    svg.cells(|(x, y)| {
+        let coord = Coordinate { x: x as i16, y: y as i16 };
+        let tile = *map.coordinates.get(&coord)?;
+        let fill = if on_loop.contains(&coord) { "#e5f5e0" } else { "#d9d9d9" };
+        Some(CellStyle::fill(fill).with_label(box_char(tile)))
+    })
+    .polyline(&points, "#31a354", true);
+    if let Some(start) = points.first() {
+        svg.marker(*start, "#fec44f");
+    }
+    svg
+}
+
 fn part1(input: &Input) -> Result<u32> {
     let map = to_map(input);
     let dist = find_max_dist(&map);
@@ -111,7 +189,10 @@ fn part2(input: &Input) -> Result<u32> {
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2};
+    use crate::{animate, part1, render};
+    use std::io::Cursor;
+    use std::time::Duration;
+    use util::viz::{Animator, Pace};
     use anyhow::Result;
     use util::Input;
 
@@ -148,6 +229,40 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    pub fn test_animate() -> Result<()> {
+        let input = Input::from_lines([
+            "-L|F7",
+            "7S-7|",
+            "L|7||",
+            "-L-J|",
+            "L|-JF",
+        ]);
+        let mut out = Vec::new();
+        animate(&input, &mut Animator::new(&mut out, Cursor::new(""), Pace::Delay(Duration::ZERO)))?;
+        let out = String::from_utf8(out)?;
+        assert_eq!(out.matches("\x1b[2J").count(), 8);
+        assert!(out.contains("Step 7 of 7"));
+        assert!(out.contains("\x1b[33mS\x1b[32m─┐"));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_render() -> Result<()> {
+        let input = Input::from_lines([
+            "-L|F7",
+            "7S-7|",
+            "L|7||",
+            "-L-J|",
+            "L|-JF",
+        ]);
+        let svg = render(&input).render();
+        assert_eq!(svg.matches("<rect").count(), 25);
+        assert!(svg.contains(r#"<polygon points="15,15 25,15 35,15 35,25 35,35 25,35 15,35 15,25""#));
+        assert!(svg.contains(r#"<circle cx="15" cy="15""#));
+        Ok(())
+    }
+
     // #[test]
     // pub fn test_part2() -> Result<()> {
     //     let input = Input::from_lines([
@@ -1,6 +1,30 @@
 use itertools::Itertools;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use util::Input;
+use util::viz::svg::{render_path_from_args, CellStyle, Svg};
+
+/// Usage: day11 [--render <out.svg>]
+///
+/// --render writes a picture of the galaxies, with the rows and columns that expand highlighted.
+fn main() -> Result<()> {
+    let input = Input::load("day11/input")?;
+    let mut args = std::env::args().skip(1).collect_vec();
+
+    if let Some(path) = render_path_from_args(&mut args).map_err(|e| anyhow!(e))? {
+        render(&input).write(&path)?;
+        println!("Wrote {}", path.display());
+    }
+    if let Some(arg) = args.first() {
+        return Err(anyhow!("Unknown argument: {}", arg));
+    }
+
+    println!("Part 1:");
+    println!("{}", part1(&input)?);
+
+    println!("Part 2:");
+    println!("{}", part2(&input)?);
+    Ok(())
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Coord { x: u64, y: u64 }
@@ -10,12 +34,6 @@ struct Image {
     galaxies: Vec<Coord>
 }
 
-impl Coord {
-    fn manhattan(&self, other: &Coord) -> u64 {
-        other.x.abs_diff(self.x) + other.y.abs_diff(self.y)
-    }
-}
-
 fn to_image(input: &Input) -> Image {
     let lines: Vec<_> = input.as_lines().map(|s| s.to_string()).collect();
 
@@ -26,81 +44,105 @@ fn to_image(input: &Input) -> Image {
     return Image { galaxies }
 }
 
-fn expand(image: &Image, amount: u64) -> Image {
-    fn expand_vert(image: &Image, amount: u64) -> Image {
-        // make y distances larger
-        // find all empty rows
-        let max_y = image.galaxies.iter().map(|c| c.y).max().unwrap_or_else(|| panic!("No max Y"));
-        let empty_ys = (0..=max_y).rev().filter(|y| {
-            let first_gs_at_y = image.galaxies.iter().find(|g| g.y == *y);
-            first_gs_at_y.is_none()
-        }).collect_vec();
-
-        let galaxies = image.galaxies.to_vec();
-        let new_gs = empty_ys.iter().fold(galaxies, |old_gs, empty_y| {
-            // add 1 to all y coordinates > empty_y
-            let below = old_gs.iter().filter(|g| g.y < *empty_y).map(|c| c.clone()).collect_vec();
-            let above = old_gs.iter().filter_map(|g| if g.y > *empty_y { Some(Coord { x: g.x, y: g.y + amount - 1 }) } else { None }).collect_vec();
-
-            let v = below.into_iter().chain(above.into_iter()).collect_vec();
-            v
-        });
-        Image { galaxies: new_gs }
-    }
-
-    fn expand_horiz(image: &Image, amount: u64) -> Image { 
-        // make x distances larger
-        // find all empty cols
-        let max_x = image.galaxies.iter().map(|c| c.x).max().unwrap_or_else(|| panic!("No max X"));
-        let empty_xs = (0..=max_x).rev().filter(|x| {
-            let first_gs_at_x = image.galaxies.iter().find(|g| g.x == *x);
-            first_gs_at_x.is_none()
-        }).collect_vec();
-
-        let galaxies = image.galaxies.to_vec();
-        let new_gs = empty_xs.iter().fold(galaxies, |old_gs, empty_x| {
-            // add 1 to all x coordinates > empty_x
-            let below = old_gs.iter().filter(|g| g.x < *empty_x).map(|c| c.clone()).collect_vec();
-            let above = old_gs.iter().filter_map(|g| if g.x > *empty_x { Some(Coord { x: g.x + amount - 1, y: g.y }) } else { None }).collect_vec();
-
-            let v = below.into_iter().chain(above.into_iter()).collect_vec();
-            v
-        });
-        Image { galaxies: new_gs }
-    }
-
-    let i1 = expand_vert(image, amount);
-    expand_horiz(&i1, amount)
+/// Prefix-sum offset table for one axis: for each coordinate up to the largest one, the number of
+/// empty lines before it. Expanding by a factor moves coordinate v to v + empty_before[v] * (factor - 1).
+fn empty_before(values: &[u64]) -> Vec<u64> {
+    let max = values.iter().max().copied().unwrap_or(0) as usize;
+    let mut occupied = vec![false; max + 1];
+    for v in values.iter() {
+        occupied[*v as usize] = true;
+    }
+    occupied.iter().scan(0, |empty, occ| {
+        let before = *empty;
+        if !occ { *empty += 1; }
+        Some(before)
+    }).collect_vec()
 }
 
-fn galaxy_pairs<'a>(image: &'a Image) -> Vec<(&'a Coord, &'a Coord)> {
-    let mut pairs: Vec<(&'a Coord, &'a Coord)> = Vec::new();
-    for (idx, g) in image.galaxies.iter().enumerate() {
-        for g2 in image.galaxies.iter().skip(idx + 1) {
-            pairs.push((g, g2))
+/// Sum of |a - b| over all pairs of values, in O(n log n): once sorted, each value is larger than
+/// all the values before it.
+fn pairwise_distance_sum(values: &mut [u64]) -> u128 {
+    values.sort_unstable();
+    let mut prefix: u128 = 0;
+    let mut sum: u128 = 0;
+    for (idx, v) in values.iter().enumerate() {
+        sum += *v as u128 * idx as u128 - prefix;
+        prefix += *v as u128;
+    }
+    sum
+}
+
+/// Sums over all galaxy pairs of the unexpanded distance, and of the number of empty lines crossed.
+/// Since expansion only widens the empty lines, the expanded sum for a factor f is
+/// distance + empty_lines * (f - 1).
+struct DistanceSums {
+    distance: u128,
+    empty_lines: u128,
+}
+
+impl Image {
+    fn distance_sums(&self) -> DistanceSums {
+        let mut sums = DistanceSums { distance: 0, empty_lines: 0 };
+        let axes = [
+            self.galaxies.iter().map(|g| g.x).collect_vec(),
+            self.galaxies.iter().map(|g| g.y).collect_vec(),
+        ];
+        for mut values in axes {
+            let offsets = empty_before(&values);
+            let mut empties = values.iter().map(|v| offsets[*v as usize]).collect_vec();
+            sums.distance += pairwise_distance_sum(&mut values);
+            sums.empty_lines += pairwise_distance_sum(&mut empties);
         }
+        sums
     }
-    pairs
 }
 
-fn part(input: &Input, amount: u64) -> Result<u64> {
+/// Draws the unexpanded image: a marker per galaxy, on top of highlighted empty rows and columns.
+fn render(input: &Input) -> Svg {
     let image = to_image(input);
-    let expanded = expand(&image, amount);
-    let result: u64 = galaxy_pairs(&expanded).iter().map(|(a, b)| a.manhattan(b)).sum();
-    Ok(result)
+    let lines = input.as_lines().collect_vec();
+    let (width, height) = (lines.first().map(|l| l.len()).unwrap_or(0), lines.len());
+    let empty_cols = (0..width).filter(|x| image.galaxies.iter().all(|g| g.x != *x as u64)).collect_vec();
+    let empty_rows = (0..height).filter(|y| image.galaxies.iter().all(|g| g.y != *y as u64)).collect_vec();
+
+    let mut svg = Svg::new(width, height);
+    svg.cells(|(x, y)| {
+        let expands = empty_cols.contains(&x) || empty_rows.contains(&y);
+        Some(CellStyle::fill(if expands { "#fde68a" } else { "#1f2937" }))
+    });
+    for g in image.galaxies.iter() {
+        svg.marker((g.x as usize, g.y as usize), "#f9fafb");
+    }
+    svg
+}
+
+/// Sums the pairwise galaxy distances for each expansion factor.
+fn parts(input: &Input, factors: &[u64]) -> Result<Vec<u128>> {
+    let sums = to_image(input).distance_sums();
+    factors.iter().map(|factor| {
+        if *factor == 0 {
+            return Err(anyhow!("Expansion factor must be at least 1"));
+        }
+        Ok(sums.distance + sums.empty_lines * (*factor as u128 - 1))
+    }).collect()
 }
 
-fn part1(input: &Input) -> Result<u64> {
+fn part(input: &Input, amount: u64) -> Result<u128> {
+    Ok(parts(input, &[amount])?[0])
+}
+
+fn part1(input: &Input) -> Result<u128> {
     part(input, 2)
 }
 
-fn part2(input: &Input) -> Result<u64> {
+fn part2(input: &Input) -> Result<u128> {
     part(input, 1000000)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2, part};
+    use input_gen::day11::generate;
+    use crate::{part1, part2, part, parts, render, to_image};
     use anyhow::Result;
     use util::Input;
 
@@ -138,4 +180,54 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 458191688761);
         Ok(())
     }
+
+    fn brute_force(input: &Input, factors: &[u64]) -> Vec<u128> {
+        let image = to_image(input);
+        factors.iter().map(|f| {
+            // Expand every galaxy by counting the empty lines before it, then sum over all pairs.
+            let expanded = image.galaxies.iter().map(|g| {
+                let empty_xs = (0..g.x).filter(|x| image.galaxies.iter().all(|o| o.x != *x)).count() as u128;
+                let empty_ys = (0..g.y).filter(|y| image.galaxies.iter().all(|o| o.y != *y)).count() as u128;
+                (g.x as u128 + empty_xs * (*f as u128 - 1), g.y as u128 + empty_ys * (*f as u128 - 1))
+            }).collect::<Vec<_>>();
+            expanded.iter().enumerate().flat_map(|(idx, a)| expanded[idx + 1..].iter().map(move |b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1))).sum::<u128>()
+        }).collect()
+    }
+
+    #[test]
+    pub fn test_parts_against_brute_force() -> Result<()> {
+        let input = Input::load("example")?;
+        let factors = [1u64, 2, 10, 100, 1_000_000];
+        assert_eq!(parts(&input, &factors)?, brute_force(&input, &factors));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_huge_factor() -> Result<()> {
+        let input = Input::load("example")?;
+        let factor = u64::MAX;
+        // 292 unexpanded, 82 empty lines crossed (from the 374 for a factor of 2).
+        assert_eq!(part(&input, factor)?, 292 + 82 * (factor as u128 - 1));
+        assert!(parts(&input, &[0]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_render() -> Result<()> {
+        let input = Input::load("example")?;
+        let svg = render(&input).render();
+        assert_eq!(svg.matches("<rect").count(), 100);
+        // Two empty rows and three empty columns, overlapping in six cells.
+        assert_eq!(svg.matches("#fde68a").count(), 2 * 10 + 3 * 10 - 6);
+        assert_eq!(svg.matches("<circle").count(), 9);
+        assert!(svg.contains(r#"<circle cx="35" cy="5""#));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_generate() -> Result<()> {
+        let input = Input::normalize(generate(20, 3));
+        assert_eq!(vec![part1(&input)?, part2(&input)?], brute_force(&input, &[2, 1_000_000]));
+        Ok(())
+    }
 }
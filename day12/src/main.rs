@@ -1,22 +1,14 @@
 use anyhow::Result;
 use itertools::Itertools;
-use util::Input;
+use util::{FromInput, Input};
 
+#[derive(FromInput)]
+#[input("{damaged} {groups: csv}")]
 struct Record {
     damaged: String,
     groups: Vec<usize>,
 }
 
-fn parse_line(line: &str) -> Record {
-    match line.split_ascii_whitespace().collect_vec()[..] {
-        [a, b] => {
-            let groups = b.split(",").map(|s| s.parse::<usize>().unwrap()).collect_vec();
-            Record { damaged: a.to_string(), groups }
-        },
-        _ => panic!("Unexpected: {}", line),
-    }
-}
-
 fn matches_damaged(damaged: &str, candidate: &str) -> bool {
     candidate.len() == damaged.len() && damaged.chars().zip(candidate.chars()).all(|(dc, cc)| {
         dc == '?' || dc == cc
@@ -80,10 +72,8 @@ fn combos(record: &Record) -> u32 {
 }
 
 fn part1(input: &Input) -> Result<u32> {
-    let res = input.as_lines().fold(0, |acc, line| {
-        let rec = parse_line(line);
-        acc + combos(&rec)
-    });
+    let records: Vec<Record> = input.parse_lines()?;
+    let res = records.iter().fold(0, |acc, rec| acc + combos(rec));
     Ok(res)
 }
 
@@ -93,7 +83,7 @@ fn part2(input: &Input) -> Result<u32> {
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2};
+    use crate::part1;
     use anyhow::Result;
     use util::Input;
 
@@ -1,16 +1,16 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 use util::Input;
 use std::fmt;
-use std::collections::HashSet;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Coord { x: usize, y: usize }
 
+/// A pattern with each row and column encoded as a bitmask, bit i set when the i-th cell is rock.
 #[derive(Debug)]
 struct Pattern {
-    rows: Vec<String>,
-    columns: Vec<String>
+    rows: Vec<u64>,
+    columns: Vec<u64>,
 }
 
 #[derive(PartialEq, Debug, Hash, Eq, Clone, Copy)]
@@ -25,149 +25,113 @@ struct ReflectionLine {
     line_type: Orientation
 }
 
+/// A reflection line that appears once the given cells are flipped. Each cell could equally be
+/// fixed by flipping its mirror image instead; the one before the line is reported.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Reflection {
+    line: ReflectionLine,
+    flips: Vec<Coord>,
+}
+
 impl fmt::Display for Pattern {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for row in self.rows.iter() {
-            writeln!(f, "{}", row)?;
+            let line: String = (0..self.columns.len()).map(|x| if row & (1 << x) != 0 { '#' } else { '.' }).collect();
+            writeln!(f, "{}", line)?;
         }
         Ok(())
     }
 }
 
-fn flip_char(ch: char) -> char {
-    if ch == '#' { '.' } else { '#' }
-}
-
-fn flip(str: &String, index: usize) -> String {
-    let new_str: String = str.chars().enumerate().map(|(idx, ch)| if idx == index { flip_char(ch) } else { ch }).collect();
-    new_str
-}
-
-fn rows_to_cols(rows: &Vec<String>) -> Vec<String> {
-    let width = rows.first().unwrap().len();
-
-    let columns = (0..width).map(|i| {
-        let chars: String = rows.iter().map(|l| match l.chars().nth(i) {
-            Some(ch) => ch,
-            None => panic!("No char at index {} in {}", i, l),
-        }).collect();
-        chars
+fn new_pattern(lines: &[&str]) -> Result<Pattern> {
+    let width = lines.first().map(|l| l.len()).unwrap_or(0);
+    if width > 64 || lines.len() > 64 {
+        return Err(anyhow!("Pattern of {}x{} doesn't fit in 64-bit masks", width, lines.len()));
+    }
+    let rows = lines.iter().map(|line| {
+        line.bytes().enumerate().fold(0u64, |mask, (x, b)| if b == b'#' { mask | 1 << x } else { mask })
     }).collect_vec();
-
-    columns
-}
-
-fn new_pattern(rows: &Vec<String>) -> Pattern {
-    let columns = rows_to_cols(rows);
-    Pattern { rows: rows.to_vec(), columns }
-}
-
-fn to_patterns(input: &Input) -> Vec<Pattern> {
-    let lines = input.as_lines().map(|s| s.to_string()).collect_vec();
-    let chunks = lines.split(|line| *line == "").map(|chunk| chunk.to_vec()).collect_vec();
-
-    let v = chunks.iter().map(|chunk| {
-        let p = new_pattern(chunk);
-        p
+    let columns = (0..width).map(|x| {
+        rows.iter().enumerate().fold(0u64, |mask, (y, row)| if row & (1 << x) != 0 { mask | 1 << y } else { mask })
     }).collect_vec();
-    v
+    Ok(Pattern { rows, columns })
 }
 
-fn has_reflection_at_index(items: &Vec<String>, mirrored_idx: usize) -> bool {
-    for idx in (0..=mirrored_idx).rev() {
-        let d = mirrored_idx - idx;
-        let mir_idx = mirrored_idx + d + 1;
-        let item_opt = items.get(idx);
-        let mir_opt = items.get(mir_idx);
-        match (item_opt, mir_opt) {
-            (Some(item), Some(mirrored)) if *mirrored == *item => {},
-            (Some(_), None) => {}, // mirror item doesn't exist, ignore
-            _ => return false, // mismatch, no reflection
-        }
-    }
-
-    true
+fn to_patterns(input: &Input) -> Result<Vec<Pattern>> {
+    input.blocks().iter().map(|block| {
+        // Some hand-written examples use spaces for ash.
+        block.validate_grid(".# ")?;
+        new_pattern(&block.as_lines().collect_vec())
+    }).collect()
 }
 
-fn reflection_lines_for_items(items: &Vec<String>, line_type: Orientation) -> Vec<ReflectionLine> {
-    let v = (0..(items.len()-1)).into_iter().filter_map(|mirrored_idx| {
-        let has_reflection = has_reflection_at_index(items, mirrored_idx);
-        if has_reflection {
-            Some(ReflectionLine { lines_before: mirrored_idx + 1, line_type })
-        } else { None }
-    }).collect_vec();
-    v
+/// Finds the mirror positions among the items (rows or columns) where the items on either side
+/// differ in exactly `smudges` cells in total, summing the Hamming distance of each mirrored pair.
+/// Returns the number of items before the mirror and the differing (item index, bit) cells.
+fn reflections_for_items(items: &[u64], smudges: u32) -> Vec<(usize, Vec<(usize, usize)>)> {
+    (1..items.len()).filter_map(|lines_before| {
+        let pairs = (0..lines_before).rev().zip(lines_before..items.len());
+        let mut distance = 0;
+        for (a, b) in pairs.clone() {
+            distance += (items[a] ^ items[b]).count_ones();
+            if distance > smudges {
+                return None;
+            }
+        }
+        if distance != smudges {
+            return None;
+        }
+        let cells = pairs.flat_map(|(a, b)| {
+            let diff = items[a] ^ items[b];
+            (0..64).filter(move |bit| diff & (1 << bit) != 0).map(move |bit| (a, bit))
+        }).collect_vec();
+        Some((lines_before, cells))
+    }).collect_vec()
 }
 
-fn line_value(rl: &ReflectionLine) -> usize {
-    if rl.line_type == Orientation::Horizontal { rl.lines_before * 100 } else { rl.lines_before }
+/// All reflection lines that appear after repairing exactly `smudges` cells.
+fn reflections(pattern: &Pattern, smudges: u32) -> Vec<Reflection> {
+    let horizontal = reflections_for_items(&pattern.rows, smudges).into_iter().map(|(lines_before, cells)| Reflection {
+        line: ReflectionLine { lines_before, line_type: Orientation::Horizontal },
+        flips: cells.into_iter().map(|(y, x)| Coord { x, y }).collect(),
+    });
+    let vertical = reflections_for_items(&pattern.columns, smudges).into_iter().map(|(lines_before, cells)| Reflection {
+        line: ReflectionLine { lines_before, line_type: Orientation::Vertical },
+        flips: cells.into_iter().map(|(x, y)| Coord { x, y }).collect(),
+    });
+    horizontal.chain(vertical).collect_vec()
 }
 
 fn reflection_lines(pattern: &Pattern) -> Vec<ReflectionLine> {
-    let horizontal = reflection_lines_for_items(&pattern.rows, Orientation::Horizontal);
-    let vertical = reflection_lines_for_items(&pattern.columns, Orientation::Vertical);
-
-    let v = horizontal.into_iter().chain(vertical.into_iter()).collect_vec();
-    v
+    reflections(pattern, 0).into_iter().map(|r| r.line).collect_vec()
 }
 
-fn value_of(pattern: &Pattern) -> usize {
-    let v = reflection_lines(pattern);
-    assert_eq!(1, v.len());
-    line_value(v.first().unwrap())
+fn line_value(rl: &ReflectionLine) -> usize {
+    if rl.line_type == Orientation::Horizontal { rl.lines_before * 100 } else { rl.lines_before }
 }
 
-fn pattern_variants(pattern: &Pattern) -> Vec<Pattern> {
-    fn pattern_with_flip(pattern: &Pattern, r: usize, c: usize) -> Pattern {
-        let new_rows = pattern.rows.iter().enumerate().map(|(r_idx, row)| {
-            if r_idx == r {
-                flip(row, c)
-            } else { row.to_string() }
-        }).collect_vec();
-        new_pattern(&new_rows)
-    }
-
-    let width = pattern.rows.get(0).unwrap().len();
-    let v = (0..pattern.rows.len()).flat_map(|r| {
-        (0..width).map(move |c| pattern_with_flip(pattern, r, c))
-    }).collect_vec();
-    v
+/// Sums the values of the single reflection line in each pattern after repairing exactly
+/// `smudges` cells.
+fn summarize(input: &Input, smudges: u32) -> Result<usize> {
+    to_patterns(input)?.iter().map(|pattern| {
+        match reflections(pattern, smudges).as_slice() {
+            [reflection] => Ok(line_value(&reflection.line)),
+            found => Err(anyhow!("Found {} reflection lines with {} smudges in:\n{}", found.len(), smudges, pattern)),
+        }
+    }).sum()
 }
 
 fn part1(input: &Input) -> Result<u32> {
-    let patterns = to_patterns(input);
-    let res = patterns.iter().fold(0, |acc, p| acc + value_of(p));
-    Ok(res as u32)
+    Ok(summarize(input, 0)? as u32)
 }
 
 fn part2(input: &Input) -> Result<u32> {
-    let patterns = to_patterns(input);
-    let res = patterns.iter().fold(0, |acc, p| {
-        let original_lines: HashSet<ReflectionLine> = reflection_lines(p).into_iter().collect();
-        let variants = pattern_variants(p);
-        let fixed = variants.iter().filter_map(|v| {
-            let variant_lines: HashSet<ReflectionLine> = reflection_lines(v).into_iter().collect();
-            let diff_lines = variant_lines.difference(&original_lines).map(|rl| rl.clone()).collect_vec();
-            if diff_lines.len() > 0 { Some(diff_lines) } else { None }
-        }).next();
-
-        let val = match fixed {
-            Some(rls) if rls.len() == 1 => {
-                line_value(rls.first().unwrap())
-            },
-            Some(rls) => panic!("Found {} different reflection lines", rls.len()),
-            None => panic!("No pattern fix found for:\n{}", p),
-        };
-
-        acc + val
-    });
-
-    Ok(res as u32)
+    Ok(summarize(input, 1)? as u32)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2, reflection_lines, to_patterns, ReflectionLine, Orientation};
+    use crate::{part1, part2, reflection_lines, reflections, to_patterns, Coord, ReflectionLine, Orientation};
     use anyhow::Result;
     use util::Input;
 
@@ -224,7 +188,7 @@ mod test {
     #[test]
     pub fn test_reflection_lines_ex3() -> Result<()> {
         let input = Input::load("ex3")?;
-        let patterns = to_patterns(&input);
+        let patterns = to_patterns(&input)?;
         let pattern = patterns.first().unwrap();
         let rls = reflection_lines(pattern);
         assert_eq!(rls, vec![ReflectionLine { lines_before: 12, line_type: Orientation::Horizontal }]);
@@ -234,7 +198,7 @@ mod test {
     #[test]
     pub fn test_reflection_lines_ex4() -> Result<()> {
         let input = Input::load("ex4")?;
-        let patterns = to_patterns(&input);
+        let patterns = to_patterns(&input)?;
         let pattern = patterns.first().unwrap();
         let rls = reflection_lines(pattern);
         assert_eq!(rls, vec![
@@ -247,7 +211,7 @@ mod test {
     #[test]
     pub fn test_reflection_lines_ex5() -> Result<()> {
         let input = Input::load("ex5")?;
-        let patterns = to_patterns(&input);
+        let patterns = to_patterns(&input)?;
         let pattern = patterns.first().unwrap();
         let rls = reflection_lines(pattern);
         assert_eq!(rls, vec![ReflectionLine { lines_before: 12, line_type: Orientation::Vertical }]);
@@ -257,7 +221,7 @@ mod test {
     #[test]
     pub fn test_reflection_lines_ex6() -> Result<()> {
         let input = Input::load("ex6")?;
-        let patterns = to_patterns(&input);
+        let patterns = to_patterns(&input)?;
         let pattern = patterns.first().unwrap();
         let rls = reflection_lines(pattern);
         assert_eq!(rls, vec![
@@ -266,4 +230,48 @@ mod test {
         ]);
         Ok(())
     }
+
+    #[test]
+    pub fn test_smudge_cells() -> Result<()> {
+        let input = Input::load("example")?;
+        let patterns = to_patterns(&input)?;
+        let fixes = patterns.iter().map(|p| reflections(p, 1)).collect::<Vec<_>>();
+        assert_eq!(fixes[0].len(), 1);
+        assert_eq!(fixes[0][0].line, ReflectionLine { lines_before: 3, line_type: Orientation::Horizontal });
+        assert_eq!(fixes[0][0].flips, vec![Coord { x: 0, y: 0 }]);
+        assert_eq!(fixes[1].len(), 1);
+        assert_eq!(fixes[1][0].line, ReflectionLine { lines_before: 1, line_type: Orientation::Horizontal });
+        assert_eq!(fixes[1][0].flips, vec![Coord { x: 4, y: 0 }]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_flips_repair_pattern() -> Result<()> {
+        // Flipping the reported cells must leave a clean reflection at the same line.
+        let input = Input::load("input")?;
+        for pattern in to_patterns(&input)? {
+            let text = pattern.to_string();
+            for smudges in 0..=3 {
+                for reflection in reflections(&pattern, smudges) {
+                    assert_eq!(reflection.flips.len(), smudges as usize);
+                    let mut grid = text.lines().map(|l| l.chars().collect::<Vec<_>>()).collect::<Vec<_>>();
+                    for Coord { x, y } in reflection.flips.iter() {
+                        grid[*y][*x] = if grid[*y][*x] == '#' { '.' } else { '#' };
+                    }
+                    let repaired = Input::from_lines(grid.iter().map(|row| row.iter().collect::<String>()));
+                    let repaired = &to_patterns(&repaired)?[0];
+                    assert!(reflection_lines(repaired).contains(&reflection.line));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_spaces_at_line_ends() -> Result<()> {
+        // Loading keeps trailing spaces, so rows using spaces for ash stay as wide as the others.
+        let input = Input::normalize("## \r\n## \r\n#.#\r\n".to_string());
+        assert_eq!(part1(&input)?, 100);
+        Ok(())
+    }
 }
@@ -1,9 +1,29 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 use indexmap::IndexMap;
 
 use util::Input;
+use util::viz::{Animator, Color, Frame, Pace};
 use std::fmt;
+use std::io::{BufRead, Write};
+
+/// Usage: day14 [--animate] [--delay <ms> | --step]
+///
+/// With any of the flags the spin cycles on the input are animated instead of solving the puzzle.
+fn main() -> Result<()> {
+    let input = Input::load("day14/input")?;
+
+    if let Some(pace) = Pace::from_args(std::env::args().skip(1)).map_err(|e| anyhow!(e))? {
+        return animate(&input, &mut Animator::terminal(pace));
+    }
+
+    println!("Part 1:");
+    println!("{}", part1(&input)?);
+
+    println!("Part 2:");
+    println!("{}", part2(&input)?);
+    Ok(())
+}
 
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
 struct Coordinate {
@@ -120,45 +140,73 @@ impl Map {
     }
 }
 
-fn to_map(input: &Input) -> Map {
+fn to_map(input: &Input) -> Result<Map> {
+    input.validate_grid(".#O")?;
     let lines = input.as_lines().collect_vec();
     let coordinates: IndexMap<_, _> = lines.iter().enumerate().flat_map(|(y, line)| {
         line.chars().enumerate().filter(|(_, ch)| *ch != '.').map(move |(x, ch)| (Coordinate { x: x as u16, y: y as u16 }, ch))
     }).collect();
     let height = lines.len() as u16;
     let width = lines.first().unwrap().len() as u16;
-    Map { coordinates, width, height }
+    Ok(Map { coordinates, width, height })
+}
+
+fn rock_color(ch: char) -> Color {
+    match ch {
+        'O' => Color::Yellow,
+        '#' => Color::Gray,
+        _ => Color::Default,
+    }
+}
+
+/// Shows the map after every tilt of the spin cycles, until the map after a cycle has been seen
+/// before and the rocks would just repeat themselves.
+fn animate<W: Write, R: BufRead>(input: &Input, animator: &mut Animator<W, R>) -> Result<()> {
+    type Tilt = fn(&Map) -> Map;
+    let tilts: [(&str, Tilt); 4] = [
+        ("north", Map::tilt_north),
+        ("west", Map::tilt_west),
+        ("south", Map::tilt_south),
+        ("east", Map::tilt_east),
+    ];
+    let mut map = to_map(input)?;
+    let mut seen = vec![map.clone()];
+    let mut cycle = 1;
+    loop {
+        for (direction, tilt) in tilts.iter() {
+            map = tilt(&map);
+            let caption = format!("Cycle {}, tilted {}, load {}", cycle, direction, map.calc_load());
+            if !animator.show(&Frame::from_text(&map.to_string(), rock_color).with_caption(caption))? {
+                return Ok(());
+            }
+        }
+        if seen.contains(&map) {
+            return Ok(());
+        }
+        seen.push(map.clone());
+        cycle += 1;
+    }
 }
 
 fn part1(input: &Input) -> Result<u32> {
-    let map = to_map(input);
+    let map = to_map(input)?;
     let new_map = map.tilt_north();
     let load = new_map.calc_load();
     Ok(load)
 }
 
 fn part2(input: &Input) -> Result<u32> {
-    let m = to_map(input);
+    let m = to_map(input)?;
     let mut maps = vec![m]; // original map has index 0, meaning subsequent indexes match no. of cycles
-    let mut remaining: Option<usize> = None;
     loop {
-        let last = maps.last().unwrap();
-        let next = last.cycle();
+        let next = maps.last().unwrap().cycle();
         let cycles = maps.len();
 
-        match remaining {
-            None => {
-                let eq_map = maps.iter().enumerate().find(|(_, mm)| next == **mm);
-                if let Some(x) = eq_map {
-                    let period = cycles - x.0;
-                    remaining = Some((1000000000 - x.0) % period - 1); // -1 as we'll cycle once before processing remaining
-                }
-            },
-            Some(r) if r > 0 => remaining = Some(r - 1),
-            _ => {
-                let load = next.calc_load();
-                return Ok(load as u32);
-            }
+        if let Some((first, _)) = maps.iter().enumerate().find(|(_, mm)| next == **mm) {
+            // The maps repeat from index `first` on, so the map after all the cycles was already seen.
+            let period = cycles - first;
+            let load = maps[first + (1000000000 - first) % period].calc_load();
+            return Ok(load as u32);
         }
         maps.push(next);
     }
@@ -166,7 +214,10 @@ fn part2(input: &Input) -> Result<u32> {
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2};
+    use crate::{animate, part1, part2};
+    use std::io::Cursor;
+    use std::time::Duration;
+    use util::viz::{Animator, Pace};
     use anyhow::Result;
     use util::Input;
 
@@ -197,4 +248,29 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 100876); // 38 seconds!!
         Ok(())
     }
+
+    #[test]
+    pub fn test_animate() -> Result<()> {
+        let input = Input::load("example")?;
+        let mut out = Vec::new();
+        animate(&input, &mut Animator::new(&mut out, Cursor::new(""), Pace::Delay(Duration::ZERO)))?;
+        let out = String::from_utf8(out)?;
+        let frames = out.matches("\x1b[2J").count();
+        assert!(frames > 0 && frames % 4 == 0);
+        assert!(out.contains("Cycle 1, tilted north, load 136"));
+
+        // Stepping stops when the user quits.
+        let mut out = Vec::new();
+        animate(&input, &mut Animator::new(&mut out, Cursor::new("\nq\n"), Pace::Step))?;
+        assert_eq!(String::from_utf8(out)?.matches("\x1b[2J").count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_part2_settles_immediately() -> Result<()> {
+        // The map repeats with period 1, so the target cycle is the first repeated map.
+        let input = Input::from_lines(["O.", ".."]);
+        assert_eq!(part2(&input)?, 1);
+        Ok(())
+    }
 }
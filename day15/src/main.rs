@@ -1,9 +1,10 @@
-use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 use util::Input;
-use regex::Regex;
+use util::parse::{alt, key_value, map, parse, tag, terminated, unsigned, word};
 
 fn hash(s: &str) -> u32 {
     let mut hash = 0;
@@ -13,6 +14,100 @@ fn hash(s: &str) -> u32 {
     hash
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Instruction {
+    /// "label-": take the lens with the label out of its box.
+    Remove { label: String },
+    /// "label=n": replace the lens with the label, or add it at the back of its box.
+    Insert { label: String, focal_length: u8 },
+}
+
+impl Instruction {
+    fn label(&self) -> &str {
+        match self {
+            Instruction::Remove { label } | Instruction::Insert { label, .. } => label,
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let instruction = alt(
+            map(key_value(word(), "=", unsigned::<u8>()), |(label, focal_length)| {
+                Instruction::Insert { label: label.to_string(), focal_length }
+            }),
+            map(terminated(word(), tag("-")), |label| Instruction::Remove { label: label.to_string() }),
+        );
+        parse(instruction, s).map_err(|e| anyhow!("Instruction \"{}\": {}", s, e))
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Remove { label } => write!(f, "{}-", label),
+            Instruction::Insert { label, focal_length } => write!(f, "{}={}", label, focal_length),
+        }
+    }
+}
+
+/// The 256 boxes of the HASHMAP, each holding (label, focal length) lenses in order.
+struct LensBoxes {
+    boxes: Vec<Vec<(String, u8)>>,
+}
+
+impl LensBoxes {
+    fn new() -> LensBoxes {
+        LensBoxes { boxes: vec![Vec::new(); 256] }
+    }
+
+    fn apply(&mut self, instruction: &Instruction) {
+        let boxx = &mut self.boxes[hash(instruction.label()) as usize];
+        let position = boxx.iter().position(|(label, _)| label == instruction.label());
+
+        match (instruction, position) {
+            (Instruction::Remove { .. }, Some(pos)) => { boxx.remove(pos); },
+            (Instruction::Remove { .. }, None) => {},
+            (Instruction::Insert { focal_length, .. }, Some(pos)) => boxx[pos].1 = *focal_length,
+            (Instruction::Insert { label, focal_length }, None) => boxx.push((label.clone(), *focal_length)),
+        }
+    }
+
+    fn focusing_power(&self) -> u32 {
+        self.boxes.iter().enumerate().map(|(box_idx, boxx)| {
+            boxx.iter().enumerate().map(|(lens_idx, (_, focal_length))| {
+                (1 + box_idx as u32) * (1 + lens_idx as u32) * *focal_length as u32
+            }).sum::<u32>()
+        }).sum()
+    }
+}
+
+/// Lists the non-empty boxes the way the puzzle does, e.g. "Box 0: [rn 1] [cm 2]".
+impl fmt::Display for LensBoxes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (box_idx, boxx) in self.boxes.iter().enumerate().filter(|(_, b)| !b.is_empty()) {
+            let lenses = boxx.iter().map(|(label, focal_length)| format!("[{} {}]", label, focal_length)).join(" ");
+            writeln!(f, "Box {}: {}", box_idx, lenses)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_instructions(input: &Input) -> Result<Vec<Instruction>> {
+    let first_line = input.as_lines().next().ok_or_else(|| anyhow!("Empty input"))?;
+    first_line.split(',').map(|s| s.parse()).collect()
+}
+
+/// Applies the instructions one by one, writing the boxes after each step like the puzzle's walkthrough.
+fn trace(instructions: &[Instruction]) -> String {
+    let mut boxes = LensBoxes::new();
+    instructions.iter().map(|instruction| {
+        boxes.apply(instruction);
+        format!("After \"{}\":\n{}", instruction, boxes)
+    }).join("\n")
+}
 
 fn part1(input: &Input) -> Result<u32> {
     let first_line = input.as_lines().nth(0).unwrap();
@@ -22,59 +117,16 @@ fn part1(input: &Input) -> Result<u32> {
 }
 
 fn part2(input: &Input) -> Result<u32> {
-    let first_line = input.as_lines().nth(0).unwrap();
-    let instructions = first_line.split(",").collect_vec();
-    let mut boxes: HashMap<u8, Vec<(String, u8)>> = HashMap::new();
-
-    for ins in instructions {
-
-        let re = Regex::new(r"([a-z]+)([=-])([0-9]+)?").unwrap();
-        match re.captures(ins) {
-            Some(caps) => {
-                let label = caps[1].to_string();
-                let op = caps[2].to_string();
-
-                let box_idx = hash(&label) as u8;
-
-                let boxx = boxes.entry(box_idx).or_insert(Vec::new());
-
-                if op == "-" {
-                    // remove
-                    let position = boxx.iter().position(|v| v.0 == label);
-                    if let Some(pos) = position {
-                        boxx.remove(pos);
-                    }
-                } else if op == "=" {
-                    let focal_length_r = &caps[3].parse::<u8>()?;
-                    let focal_length: u8 = focal_length_r.clone();
-
-                    // add or replace
-                    let position = boxx.iter().position(|v| v.0 == label);
-                    if let Some(pos) = position {
-                        boxx[pos] = (label, focal_length);
-                    } else {
-                        boxx.push((label, focal_length));
-                    }
-                }
-
-            },
-            _ => panic!("Unrecognized: {}", ins),
-        }
+    let mut boxes = LensBoxes::new();
+    for instruction in parse_instructions(input)? {
+        boxes.apply(&instruction);
     }
-
-    let power = boxes.iter().fold(0, |acc, (box_idx, boxx)| {
-        let focusing_power = boxx.iter().enumerate().fold(0, |acc2, (lens_idx, (_, focal_length))| {
-            acc2 + (1 + *box_idx as u32) * (1 + lens_idx as u32) * *focal_length as u32
-        });
-        acc + focusing_power
-    });
-
-    Ok(power)
+    Ok(boxes.focusing_power())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{part1, part2, hash};
+    use crate::{part1, part2, hash, parse_instructions, trace};
     use anyhow::Result;
     use util::Input;
 
@@ -117,4 +169,30 @@ mod test {
         assert_eq!(part2(&input).unwrap(), 296921);
         Ok(())
     }
+
+    #[test]
+    pub fn test_trace() -> Result<()> {
+        let input = Input::from_lines(["rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7"]);
+        let expected = [
+            "After \"rn=1\":", "Box 0: [rn 1]", "",
+            "After \"cm-\":", "Box 0: [rn 1]", "",
+            "After \"qp=3\":", "Box 0: [rn 1]", "Box 1: [qp 3]", "",
+            "After \"cm=2\":", "Box 0: [rn 1] [cm 2]", "Box 1: [qp 3]", "",
+            "After \"qp-\":", "Box 0: [rn 1] [cm 2]", "",
+            "After \"pc=4\":", "Box 0: [rn 1] [cm 2]", "Box 3: [pc 4]", "",
+            "After \"ot=9\":", "Box 0: [rn 1] [cm 2]", "Box 3: [pc 4] [ot 9]", "",
+            "After \"ab=5\":", "Box 0: [rn 1] [cm 2]", "Box 3: [pc 4] [ot 9] [ab 5]", "",
+            "After \"pc-\":", "Box 0: [rn 1] [cm 2]", "Box 3: [ot 9] [ab 5]", "",
+            "After \"pc=6\":", "Box 0: [rn 1] [cm 2]", "Box 3: [ot 9] [ab 5] [pc 6]", "",
+            "After \"ot=7\":", "Box 0: [rn 1] [cm 2]", "Box 3: [ot 7] [ab 5] [pc 6]", "",
+        ];
+        assert_eq!(trace(&parse_instructions(&input)?), expected.join("\n"));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_invalid_instruction() {
+        let input = Input::from_lines(["rn=1,cm+2"]);
+        assert!(parse_instructions(&input).is_err());
+    }
 }
@@ -0,0 +1,221 @@
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::collections::{HashSet, VecDeque};
+
+use util::Input;
+use util::poly::Polynomial;
+
+fn main() -> Result<()> {
+    let input = Input::load("day21/input")?;
+
+    println!("Part 1:");
+    println!("{}", part1(&input)?);
+
+    println!("Part 2:");
+    println!("{}", part2(&input)?);
+    Ok(())
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+struct Coord {
+    x: i64,
+    y: i64,
+}
+
+struct Garden {
+    rocks: Vec<Vec<bool>>,
+    width: i64,
+    height: i64,
+    start: Coord,
+}
+
+impl Garden {
+    /// Tests if the coordinate is a rock. With tiling, the map repeats infinitely in all directions.
+    fn is_rock(&self, c: &Coord, tiled: bool) -> bool {
+        if tiled {
+            let x = c.x.rem_euclid(self.width) as usize;
+            let y = c.y.rem_euclid(self.height) as usize;
+            self.rocks[y][x]
+        } else if c.x < 0 || c.y < 0 || c.x >= self.width || c.y >= self.height {
+            true
+        } else {
+            self.rocks[c.y as usize][c.x as usize]
+        }
+    }
+
+    fn neighbours(c: &Coord) -> [Coord; 4] {
+        [
+            Coord { x: c.x - 1, y: c.y },
+            Coord { x: c.x + 1, y: c.y },
+            Coord { x: c.x, y: c.y - 1 },
+            Coord { x: c.x, y: c.y + 1 },
+        ]
+    }
+
+    /// Counts the garden plots reachable in exactly `steps` steps. Since it's always possible to step
+    /// back and forth, a plot is reachable if its distance is at most `steps` and has the same parity.
+    fn reachable(&self, steps: u64, tiled: bool) -> u64 {
+        let mut seen: HashSet<Coord> = HashSet::new();
+        let mut queue: VecDeque<(Coord, u64)> = VecDeque::new();
+        let mut count = 0;
+        seen.insert(self.start);
+        queue.push_back((self.start, 0));
+
+        while let Some((c, dist)) = queue.pop_front() {
+            if dist % 2 == steps % 2 {
+                count += 1;
+            }
+            if dist == steps {
+                continue;
+            }
+            for n in Garden::neighbours(&c) {
+                if !self.is_rock(&n, tiled) && seen.insert(n) {
+                    queue.push_back((n, dist + 1));
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Checks the shape that the extrapolation relies on: a square map with S in the centre and no
+    /// rocks in its row and column, so the walk enters every copy of the map at regular intervals.
+    fn check_extrapolatable(&self) -> Result<()> {
+        if self.width != self.height {
+            return Err(anyhow!("Can only extrapolate on a square map, not {}x{}", self.width, self.height));
+        }
+        if self.width % 2 == 0 || self.start != (Coord { x: self.width / 2, y: self.height / 2 }) {
+            return Err(anyhow!("Can only extrapolate with S in the centre of the map"));
+        }
+        let row_clear = (0..self.width).all(|x| !self.rocks[self.start.y as usize][x as usize]);
+        let column_clear = (0..self.height).all(|y| !self.rocks[y as usize][self.start.x as usize]);
+        if !row_clear || !column_clear {
+            return Err(anyhow!("Can only extrapolate with no rocks in the row and column of S"));
+        }
+        Ok(())
+    }
+
+    /// Counts the plots reachable on the infinitely tiled map for a large number of steps. The count
+    /// grows quadratically in the number of whole map widths walked, so three samples are enough to
+    /// extrapolate the rest.
+    fn reachable_extrapolated(&self, steps: u64) -> Result<u64> {
+        self.check_extrapolatable()?;
+        let width = self.width as u64;
+        let remainder = steps % width;
+        let repeats = (steps / width) as usize;
+
+        let samples = (0..3)
+            .map(|i| self.reachable(remainder + i * width, true) as i64)
+            .collect_vec();
+        if repeats < samples.len() {
+            return Ok(samples[repeats] as u64);
+        }
+
+        Polynomial::from_values(&samples)
+            .and_then(|p| p.eval(repeats as i64))
+            .and_then(|count| u64::try_from(count).ok())
+            .ok_or_else(|| anyhow!("Reachable plot count overflows after {} steps", steps))
+    }
+}
+
+fn to_garden(input: &Input) -> Result<Garden> {
+    let lines = input.as_lines().collect_vec();
+    let rocks = lines.iter().map(|line| line.chars().map(|ch| ch == '#').collect_vec()).collect_vec();
+    let start = lines
+        .iter()
+        .enumerate()
+        .find_map(|(y, line)| line.find('S').map(|x| Coord { x: x as i64, y: y as i64 }))
+        .ok_or_else(|| anyhow!("No starting position"))?;
+    let height = lines.len() as i64;
+    let width = lines.first().map(|line| line.len()).unwrap_or(0) as i64;
+    Ok(Garden { rocks, width, height, start })
+}
+
+fn part(input: &Input, steps: u64) -> Result<u64> {
+    let garden = to_garden(input)?;
+    Ok(garden.reachable(steps, false))
+}
+
+fn part1(input: &Input) -> Result<u64> {
+    part(input, 64)
+}
+
+fn part2(input: &Input) -> Result<u64> {
+    let garden = to_garden(input)?;
+    garden.reachable_extrapolated(26501365)
+}
+
+#[cfg(test)]
+mod test {
+    use input_gen::day21::generate;
+    use crate::{part, part1, part2, to_garden};
+    use anyhow::Result;
+    use util::Input;
+
+    fn example() -> Input {
+        Input::from_lines([
+            "...........",
+            ".....###.#.",
+            ".###.##..#.",
+            "..#.#...#..",
+            "....#.#....",
+            ".##..S####.",
+            ".##..#...#.",
+            ".......##..",
+            ".##.#.####.",
+            ".##..##.##.",
+            "...........",
+        ])
+    }
+
+    #[test]
+    pub fn test_part1() -> Result<()> {
+        assert_eq!(part(&example(), 6).unwrap(), 16);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_tiled() -> Result<()> {
+        let garden = to_garden(&example())?;
+        assert_eq!(garden.reachable(6, true), 16);
+        assert_eq!(garden.reachable(10, true), 50);
+        assert_eq!(garden.reachable(50, true), 1594);
+        assert_eq!(garden.reachable(100, true), 6536);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_extrapolated() -> Result<()> {
+        // In an open garden, the plots reachable in n steps form a diamond of (n + 1)^2 plots.
+        let garden = to_garden(&Input::from_lines([
+            ".....",
+            ".....",
+            "..S..",
+            ".....",
+            ".....",
+        ]))?;
+        assert_eq!(garden.reachable_extrapolated(52)?, 53 * 53);
+        assert_eq!(garden.reachable_extrapolated(1002)?, 1003 * 1003);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_extrapolated_needs_centred_start() -> Result<()> {
+        let error = |lines: &[&str]| to_garden(&Input::from_lines(lines)).unwrap().reachable_extrapolated(100).unwrap_err().to_string();
+        assert_eq!(error(&["...", "...", "..S"]), "Can only extrapolate with S in the centre of the map");
+        assert_eq!(error(&["...", ".S.", "...", "..."]), "Can only extrapolate on a square map, not 3x4");
+        assert_eq!(error(&[".#.", ".S.", "..."]), "Can only extrapolate with no rocks in the row and column of S");
+        assert!(part2(&example()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_generate() -> Result<()> {
+        let input = Input::normalize(generate(5, 3));
+        let garden = to_garden(&input)?;
+        assert_eq!(garden.reachable_extrapolated(60)?, garden.reachable(60, true));
+        part1(&input)?;
+        part2(&input)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+
+use util::Input;
+
+fn main() -> Result<()> {
+    let input = Input::load("day22/input")?;
+
+    println!("Part 1:");
+    println!("{}", part1(&input)?);
+
+    println!("Part 2:");
+    println!("{}", part2(&input)?);
+    Ok(())
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+struct Point {
+    x: u32,
+    y: u32,
+    z: u32,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+struct Brick {
+    from: Point,
+    to: Point,
+}
+
+/// The settled bricks together with the graph of which bricks rest on which.
+#[derive(Debug)]
+struct Stack {
+    bricks: Vec<Brick>,
+    /// For each brick, the bricks resting directly on top of it.
+    supports: Vec<Vec<usize>>,
+    /// For each brick, the bricks it rests directly on.
+    supported_by: Vec<Vec<usize>>,
+}
+
+impl FromStr for Point {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split(',').map(|n| n.trim().parse::<u32>()).collect_vec()[..] {
+            [Ok(x), Ok(y), Ok(z)] => Ok(Point { x, y, z }),
+            _ => Err(anyhow!("Invalid point: {}", s)),
+        }
+    }
+}
+
+impl FromStr for Brick {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (a, b) = s.split_once('~').ok_or_else(|| anyhow!("Invalid brick: {}", s))?;
+        let (a, b): (Point, Point) = (a.parse()?, b.parse()?);
+        // Normalize so that `from` holds the lowest coordinates.
+        let from = Point { x: a.x.min(b.x), y: a.y.min(b.y), z: a.z.min(b.z) };
+        let to = Point { x: a.x.max(b.x), y: a.y.max(b.y), z: a.z.max(b.z) };
+        Ok(Brick { from, to })
+    }
+}
+
+impl Brick {
+    fn footprint(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (self.from.x..=self.to.x).flat_map(move |x| (self.from.y..=self.to.y).map(move |y| (x, y)))
+    }
+
+    fn height(&self) -> u32 {
+        self.to.z - self.from.z + 1
+    }
+
+    fn dropped_to(&self, z: u32) -> Brick {
+        let from = Point { z, ..self.from };
+        let to = Point { z: z + self.height() - 1, ..self.to };
+        Brick { from, to }
+    }
+}
+
+impl Stack {
+    /// Lets the bricks fall in order of their lowest z coordinate, tracking the topmost brick at each
+    /// (x, y) position in a height map.
+    fn settle(bricks: &[Brick]) -> Stack {
+        let ordered = bricks.iter().sorted_by_key(|b| b.from.z).collect_vec();
+        let mut height_map: HashMap<(u32, u32), (u32, usize)> = HashMap::new();
+        let mut settled: Vec<Brick> = Vec::with_capacity(bricks.len());
+        let mut supports: Vec<Vec<usize>> = vec![vec![]; bricks.len()];
+        let mut supported_by: Vec<Vec<usize>> = vec![vec![]; bricks.len()];
+
+        for (idx, brick) in ordered.into_iter().enumerate() {
+            let below = brick.footprint().filter_map(|xy| height_map.get(&xy)).collect_vec();
+            let top = below.iter().map(|(z, _)| *z).max().unwrap_or(0);
+            let resting_on = below.iter().filter(|(z, _)| *z == top).map(|(_, i)| *i).unique().sorted().collect_vec();
+
+            for other in resting_on.iter() {
+                supports[*other].push(idx);
+            }
+            supported_by[idx] = resting_on;
+
+            let dropped = brick.dropped_to(top + 1);
+            for xy in dropped.footprint() {
+                height_map.insert(xy, (dropped.to.z, idx));
+            }
+            settled.push(dropped);
+        }
+
+        Stack { bricks: settled, supports, supported_by }
+    }
+
+    /// A brick can be disintegrated if every brick it supports has another brick to rest on.
+    fn can_disintegrate(&self, idx: usize) -> bool {
+        self.supports[idx].iter().all(|above| self.supported_by[*above].len() > 1)
+    }
+
+    /// Counts the other bricks that would fall if the given brick was disintegrated.
+    fn chain_reaction(&self, idx: usize) -> usize {
+        let mut removed_supports: Vec<usize> = vec![0; self.bricks.len()];
+        let mut queue = VecDeque::from([idx]);
+        let mut fallen = 0;
+
+        while let Some(removed) = queue.pop_front() {
+            for above in self.supports[removed].iter() {
+                removed_supports[*above] += 1;
+                if removed_supports[*above] == self.supported_by[*above].len() {
+                    fallen += 1;
+                    queue.push_back(*above);
+                }
+            }
+        }
+
+        fallen
+    }
+}
+
+fn parse_input(input: &Input) -> Result<Vec<Brick>> {
+    input.as_lines().map(|line| line.parse::<Brick>()).collect()
+}
+
+fn part1(input: &Input) -> Result<usize> {
+    let stack = Stack::settle(&parse_input(input)?);
+    let count = (0..stack.bricks.len()).filter(|idx| stack.can_disintegrate(*idx)).count();
+    Ok(count)
+}
+
+fn part2(input: &Input) -> Result<usize> {
+    let stack = Stack::settle(&parse_input(input)?);
+    let sum = (0..stack.bricks.len()).map(|idx| stack.chain_reaction(idx)).sum();
+    Ok(sum)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{part1, part2, parse_input, Stack};
+    use anyhow::Result;
+    use util::Input;
+
+    fn example() -> Input {
+        Input::from_lines([
+            "1,0,1~1,2,1",
+            "0,0,2~2,0,2",
+            "0,2,3~2,2,3",
+            "0,0,4~0,2,4",
+            "2,0,5~2,2,5",
+            "0,1,6~2,1,6",
+            "1,1,8~1,1,9",
+        ])
+    }
+
+    #[test]
+    pub fn test_settle() -> Result<()> {
+        let stack = Stack::settle(&parse_input(&example())?);
+        let lowest_z = stack.bricks.iter().map(|b| b.from.z).collect::<Vec<_>>();
+        assert_eq!(lowest_z, vec![1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(stack.supported_by[1], vec![0]);
+        assert_eq!(stack.supported_by[3], vec![1, 2]);
+        assert_eq!(stack.supports[5], vec![6]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_part1() -> Result<()> {
+        assert_eq!(part1(&example()).unwrap(), 5);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_part2() -> Result<()> {
+        assert_eq!(part2(&example()).unwrap(), 7);
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{anyhow, Result};
+
+use util::grid::{junction_graph, Grid, JunctionGraph, Pos};
+use util::Input;
+
+fn main() -> Result<()> {
+    let input = Input::load("day23/input")?;
+
+    println!("Part 1:");
+    println!("{}", part1(&input)?);
+
+    println!("Part 2:");
+    println!("{}", part2(&input)?);
+    Ok(())
+}
+
+/// Decides if a step between two adjacent positions is possible. Standing on a slope, the only way
+/// is downhill, unless slopes are ignored.
+fn can_step(grid: &Grid, from: Pos, to: Pos, slippery: bool) -> bool {
+    if [from, to].iter().any(|pos| grid.get(*pos).is_none_or(|ch| ch == '#')) {
+        return false;
+    }
+    if !slippery {
+        return true;
+    }
+    match grid.get(from) {
+        Some('>') => to.0 > from.0,
+        Some('<') => to.0 < from.0,
+        Some('v') => to.1 > from.1,
+        Some('^') => to.1 < from.1,
+        _ => true,
+    }
+}
+
+/// Finds the length of the longest path from `node` to `end` that doesn't visit any node twice.
+/// Visited nodes are tracked as bits in a mask.
+fn longest_path(graph: &JunctionGraph, node: usize, end: usize, visited: u64) -> Option<u32> {
+    if node == end {
+        return Some(0);
+    }
+    let visited = visited | (1 << node);
+    graph.edges[node]
+        .iter()
+        .filter(|(next, _)| visited & (1 << next) == 0)
+        .filter_map(|(next, length)| longest_path(graph, *next, end, visited).map(|rest| rest + length))
+        .max()
+}
+
+fn part(input: &Input, slippery: bool) -> Result<u32> {
+    let grid = Grid::from_input(input);
+    let start = (0..grid.width).map(|x| (x, 0)).find(|pos| grid.get(*pos) == Some('.'))
+        .ok_or_else(|| anyhow!("No start"))?;
+    let end = (0..grid.width).map(|x| (x, grid.height - 1)).find(|pos| grid.get(*pos) == Some('.'))
+        .ok_or_else(|| anyhow!("No end"))?;
+
+    let graph = junction_graph(&grid, &[start, end], |g, from, to| can_step(g, from, to, slippery));
+    if graph.nodes.len() > 64 {
+        return Err(anyhow!("Too many junctions for a 64-bit visited set: {}", graph.nodes.len()));
+    }
+    let start_idx = graph.nodes.iter().position(|n| *n == start).unwrap();
+    let end_idx = graph.nodes.iter().position(|n| *n == end).unwrap();
+
+    longest_path(&graph, start_idx, end_idx, 0).ok_or_else(|| anyhow!("No path to the end"))
+}
+
+fn part1(input: &Input) -> Result<u32> {
+    part(input, true)
+}
+
+fn part2(input: &Input) -> Result<u32> {
+    part(input, false)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{part1, part2};
+    use anyhow::Result;
+    use util::Input;
+
+    fn example() -> Input {
+        Input::from_lines([
+            "#.#####################",
+            "#.......#########...###",
+            "#######.#########.#.###",
+            "###.....#.>.>.###.#.###",
+            "###v#####.#v#.###.#.###",
+            "###.>...#.#.#.....#...#",
+            "###v###.#.#.#########.#",
+            "###...#.#.#.......#...#",
+            "#####.#.#.#######.#.###",
+            "#.....#.#.#.......#...#",
+            "#.#####.#.#.#########v#",
+            "#.#...#...#...###...>.#",
+            "#.#.#v#######v###.###v#",
+            "#...#.>.#...>.>.#.###.#",
+            "#####v#.#.###v#.#.###.#",
+            "#.....#...#...#.#.#...#",
+            "#.#########.###.#.#.###",
+            "#...###...#...#...#.###",
+            "###.###.#.###v#####v###",
+            "#...#...#.#.>.>.#.>.###",
+            "#.###.###.#.###.#.#v###",
+            "#.....###...###...#...#",
+            "#####################.#",
+        ])
+    }
+
+    #[test]
+    pub fn test_part1() -> Result<()> {
+        assert_eq!(part1(&example()).unwrap(), 94);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_part2() -> Result<()> {
+        assert_eq!(part2(&example()).unwrap(), 154);
+        Ok(())
+    }
+}
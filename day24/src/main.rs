@@ -0,0 +1,220 @@
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, ToPrimitive, Zero};
+use std::str::FromStr;
+
+use util::Input;
+
+fn main() -> Result<()> {
+    let input = Input::load("day24/input")?;
+
+    println!("Part 1:");
+    println!("{}", part1(&input)?);
+
+    println!("Part 2:");
+    println!("{}", part2(&input)?);
+    Ok(())
+}
+
+type Vec3 = [i128; 3];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hailstone {
+    position: Vec3,
+    velocity: Vec3,
+}
+
+fn parse_vec3(s: &str) -> Result<Vec3> {
+    match s.split(',').map(|n| n.trim().parse::<i128>()).collect_vec()[..] {
+        [Ok(x), Ok(y), Ok(z)] => Ok([x, y, z]),
+        _ => Err(anyhow!("Invalid vector: {}", s)),
+    }
+}
+
+impl FromStr for Hailstone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (p, v) = s.split_once('@').ok_or_else(|| anyhow!("Invalid hailstone: {}", s))?;
+        Ok(Hailstone { position: parse_vec3(p)?, velocity: parse_vec3(v)? })
+    }
+}
+
+fn sub(a: &Vec3, b: &Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// Tests if the future xy paths of two hailstones cross within the test area. All comparisons are
+/// done on the numerators of the intersection point, so no precision is lost.
+fn paths_cross_within(a: &Hailstone, b: &Hailstone, min: i128, max: i128) -> bool {
+    let [apx, apy, _] = a.position;
+    let [avx, avy, _] = a.velocity;
+    let [bpx, bpy, _] = b.position;
+    let [bvx, bvy, _] = b.velocity;
+
+    // Solve a.p + t * a.v = b.p + s * b.v for t and s using Cramer's rule.
+    let det = bvx * avy - avx * bvy;
+    if det == 0 {
+        return false; // parallel paths
+    }
+    let (dx, dy) = (bpx - apx, bpy - apy);
+    let t_num = bvx * dy - bvy * dx;
+    let s_num = avx * dy - avy * dx;
+
+    // Normalize so that the denominator is positive, which keeps the inequalities pointing the right way.
+    let (det, t_num, s_num) = if det < 0 { (-det, -t_num, -s_num) } else { (det, t_num, s_num) };
+    if t_num < 0 || s_num < 0 {
+        return false; // crossed in the past
+    }
+
+    let x_num = apx * det + avx * t_num;
+    let y_num = apy * det + avy * t_num;
+    let in_range = |n: i128| min * det <= n && n <= max * det;
+    in_range(x_num) && in_range(y_num)
+}
+
+fn parse_input(input: &Input) -> Result<Vec<Hailstone>> {
+    input.as_lines().map(|line| line.parse::<Hailstone>()).collect()
+}
+
+fn count_crossings(hailstones: &[Hailstone], min: i128, max: i128) -> usize {
+    hailstones
+        .iter()
+        .tuple_combinations()
+        .filter(|(a, b)| paths_cross_within(a, b, min, max))
+        .count()
+}
+
+/// Solves the linear system with Gauss-Jordan elimination over exact rationals.
+fn solve(mut matrix: Vec<Vec<BigRational>>, mut rhs: Vec<BigRational>) -> Option<Vec<BigRational>> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot = (col..n).find(|row| !matrix[*row][col].is_zero())?;
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let factor = BigRational::one() / &matrix[col][col];
+        matrix[col] = matrix[col].iter().map(|v| v * &factor).collect();
+        rhs[col] = &rhs[col] * &factor;
+
+        for row in (0..n).filter(|row| *row != col) {
+            let f = matrix[row][col].clone();
+            if f.is_zero() {
+                continue;
+            }
+            matrix[row] = matrix[row].iter().zip(matrix[col].iter()).map(|(a, b)| a - &f * b).collect();
+            rhs[row] = &rhs[row] - &f * &rhs[col];
+        }
+    }
+    Some(rhs)
+}
+
+/// Finds the rock position and velocity that hits every hailstone. For the rock P, V and a hailstone
+/// p, v, the collision means (P - p) x (V - v) = 0. Subtracting that equation for two different
+/// hailstones i and j cancels the non-linear P x V term, leaving three linear equations:
+///
+///     P x (v_j - v_i) + (p_j - p_i) x V = p_j x v_j - p_i x v_i
+///
+/// Two pairs of hailstones give six equations for the six unknowns.
+fn find_rock(hailstones: &[Hailstone]) -> Result<Hailstone> {
+    let rat = |n: i128| BigRational::from_integer(BigInt::from(n));
+
+    for (h0, h1, h2) in hailstones.iter().tuple_combinations() {
+        let mut matrix = vec![];
+        let mut rhs = vec![];
+        for hj in [h1, h2] {
+            let d = sub(&hj.velocity, &h0.velocity);
+            let e = sub(&hj.position, &h0.position);
+            let c = sub(&cross(&hj.position, &hj.velocity), &cross(&h0.position, &h0.velocity));
+            let rows = [
+                [0, d[2], -d[1], 0, -e[2], e[1]],
+                [-d[2], 0, d[0], e[2], 0, -e[0]],
+                [d[1], -d[0], 0, -e[1], e[0], 0],
+            ];
+            for (row, value) in rows.iter().zip(c.iter()) {
+                matrix.push(row.iter().map(|v| rat(*v)).collect_vec());
+                rhs.push(rat(*value));
+            }
+        }
+
+        if let Some(solution) = solve(matrix, rhs) {
+            let ints = solution
+                .iter()
+                .map(|v| if v.is_integer() { v.to_integer().to_i128() } else { None })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| anyhow!("Rock trajectory is not integral: {:?}", solution))?;
+            return Ok(Hailstone { position: [ints[0], ints[1], ints[2]], velocity: [ints[3], ints[4], ints[5]] });
+        }
+    }
+
+    Err(anyhow!("No three hailstones give an independent system"))
+}
+
+fn part1(input: &Input) -> Result<usize> {
+    let hailstones = parse_input(input)?;
+    Ok(count_crossings(&hailstones, 200000000000000, 400000000000000))
+}
+
+fn part2(input: &Input) -> Result<i128> {
+    let hailstones = parse_input(input)?;
+    let rock = find_rock(&hailstones)?;
+    Ok(rock.position.iter().sum())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{count_crossings, find_rock, parse_input, part2, Hailstone};
+    use anyhow::Result;
+    use util::Input;
+
+    fn example() -> Input {
+        Input::from_lines([
+            "19, 13, 30 @ -2,  1, -2",
+            "18, 19, 22 @ -1, -1, -2",
+            "20, 25, 34 @ -2, -2, -4",
+            "12, 31, 28 @ -1, -2, -1",
+            "20, 19, 15 @  1, -5, -3",
+        ])
+    }
+
+    #[test]
+    pub fn test_part1() -> Result<()> {
+        let hailstones = parse_input(&example())?;
+        assert_eq!(count_crossings(&hailstones, 7, 27), 2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_find_rock() -> Result<()> {
+        let hailstones = parse_input(&example())?;
+        assert_eq!(find_rock(&hailstones)?, Hailstone { position: [24, 13, 10], velocity: [-3, 1, 2] });
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_find_rock_large() -> Result<()> {
+        // Build hailstones at puzzle-sized coordinates that the rock hits at the given times.
+        let rock = Hailstone { position: [287430900794614, 365088263289151, 198007925007842], velocity: [-71, -293, 83] };
+        let hailstones = [(512, [40, -97, 12]), (9001, [-300, 150, 71]), (70321, [13, 0, -260]), (123456, [5, 5, 5])]
+            .iter()
+            .map(|(t, v): &(i128, [i128; 3])| {
+                let position = [0, 1, 2].map(|i| rock.position[i] + t * (rock.velocity[i] - v[i]));
+                Hailstone { position, velocity: *v }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(find_rock(&hailstones)?, rock);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_part2() -> Result<()> {
+        assert_eq!(part2(&example()).unwrap(), 47);
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use util::Input;
+
+fn main() -> Result<()> {
+    let input = Input::load("day25/input")?;
+
+    let cut = three_wire_cut(&input)?;
+    println!("Part 1:");
+    println!("{}", cut.product());
+    println!("Cut wires: {}", cut.wires());
+    Ok(())
+}
+
+/// The component wiring as an undirected graph. Nodes are indexed in name order, which keeps the
+/// search deterministic.
+struct Wiring {
+    names: Vec<String>,
+    neighbours: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Cut {
+    group_sizes: (usize, usize),
+    /// The cut wires, each with its names in order, sorted.
+    edges: Vec<(String, String)>,
+}
+
+impl Cut {
+    fn product(&self) -> usize {
+        self.group_sizes.0 * self.group_sizes.1
+    }
+
+    /// The cut wires as "a/b, c/d, ...".
+    fn wires(&self) -> String {
+        self.edges.iter().map(|(a, b)| format!("{}/{}", a, b)).join(", ")
+    }
+}
+
+fn parse_input(input: &Input) -> Result<Wiring> {
+    let connections = input
+        .as_lines()
+        .map(|line| {
+            let (from, tos) = line.split_once(':').ok_or_else(|| anyhow!("Invalid line: {}", line))?;
+            Ok((from.trim(), tos.split_ascii_whitespace().collect_vec()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let names: BTreeSet<&str> = connections.iter().flat_map(|(from, tos)| tos.iter().copied().chain([*from])).collect();
+    let index: HashMap<&str, usize> = names.iter().enumerate().map(|(idx, name)| (*name, idx)).collect();
+    let mut neighbours: Vec<Vec<usize>> = vec![vec![]; names.len()];
+    for (from, tos) in connections.iter() {
+        for to in tos {
+            neighbours[index[from]].push(index[to]);
+            neighbours[index[to]].push(index[from]);
+        }
+    }
+    for ns in neighbours.iter_mut() {
+        ns.sort();
+    }
+
+    Ok(Wiring { names: names.into_iter().map(String::from).collect(), neighbours })
+}
+
+impl Wiring {
+    /// Finds a path from source to sink in the residual graph, where every wire has capacity 1 in
+    /// both directions. Returns the predecessor of each visited node.
+    fn augmenting_path(&self, source: usize, sink: usize, flow: &HashMap<(usize, usize), i32>) -> Vec<Option<usize>> {
+        let mut pred: Vec<Option<usize>> = vec![None; self.names.len()];
+        pred[source] = Some(source);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(node) = queue.pop_front() {
+            if node == sink {
+                break;
+            }
+            for next in self.neighbours[node].iter() {
+                let used = flow.get(&(node, *next)).copied().unwrap_or(0);
+                if pred[*next].is_none() && used < 1 {
+                    pred[*next] = Some(node);
+                    queue.push_back(*next);
+                }
+            }
+        }
+        pred
+    }
+
+    /// Runs Edmonds-Karp between source and sink, giving up once the flow exceeds `limit`. If the
+    /// max flow is within the limit, returns the nodes on the source side of the minimum cut.
+    fn min_cut(&self, source: usize, sink: usize, limit: usize) -> Option<Vec<bool>> {
+        let mut flow: HashMap<(usize, usize), i32> = HashMap::new();
+
+        for _ in 0..=limit {
+            let pred = self.augmenting_path(source, sink, &flow);
+            if pred[sink].is_none() {
+                // No more augmenting paths; whatever is still reachable is the source side.
+                return Some(pred.iter().map(|p| p.is_some()).collect());
+            }
+            let mut node = sink;
+            while node != source {
+                let prev = pred[node].unwrap();
+                *flow.entry((prev, node)).or_insert(0) += 1;
+                *flow.entry((node, prev)).or_insert(0) -= 1;
+                node = prev;
+            }
+        }
+        None
+    }
+
+    /// Finds the cut of exactly `size` wires that splits the graph in two. The first node is on one
+    /// side, so trying every other node as the sink eventually finds one on the other side.
+    fn find_cut(&self, size: usize) -> Option<Cut> {
+        (1..self.names.len()).find_map(|sink| {
+            let side = self.min_cut(0, sink, size)?;
+            let edges = self.neighbours.iter().enumerate()
+                .flat_map(|(a, ns)| ns.iter().map(move |b| (a, *b)))
+                .filter(|(a, b)| side[*a] && !side[*b])
+                .map(|(a, b)| {
+                    let (x, y) = (self.names[a].clone(), self.names[b].clone());
+                    if x < y { (x, y) } else { (y, x) }
+                })
+                .sorted()
+                .collect_vec();
+            if edges.len() != size {
+                return None;
+            }
+            let in_first = side.iter().filter(|s| **s).count();
+            Some(Cut { group_sizes: (in_first, self.names.len() - in_first), edges })
+        })
+    }
+}
+
+fn three_wire_cut(input: &Input) -> Result<Cut> {
+    let wiring = parse_input(input)?;
+    wiring.find_cut(3).ok_or_else(|| anyhow!("No 3-wire cut found"))
+}
+
+fn part1(input: &Input) -> Result<usize> {
+    Ok(three_wire_cut(input)?.product())
+}
+
+#[cfg(test)]
+mod test {
+    use input_gen::day25::generate;
+    use crate::{parse_input, part1, three_wire_cut, Cut};
+    use anyhow::Result;
+    use util::Input;
+
+    fn example() -> Input {
+        Input::from_lines([
+            "jqt: rhn xhk nvd",
+            "rsh: frs pzl lsr",
+            "xhk: hfx",
+            "cmg: qnr nvd lhk bvb",
+            "rhn: xhk bvb hfx",
+            "bvb: xhk hfx",
+            "pzl: lsr hfx nvd",
+            "qnr: nvd",
+            "ntq: jqt hfx bvb xhk",
+            "nvd: lhk",
+            "lsr: lhk",
+            "rzs: qnr cmg lsr rsh",
+            "frs: qnr lhk lsr",
+        ])
+    }
+
+    #[test]
+    pub fn test_part1() -> Result<()> {
+        assert_eq!(part1(&example()).unwrap(), 54);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_cut_edges() -> Result<()> {
+        let wiring = parse_input(&example())?;
+        let to_pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(wiring.find_cut(3), Some(Cut {
+            group_sizes: (6, 9),
+            edges: vec![to_pair("bvb", "cmg"), to_pair("hfx", "pzl"), to_pair("jqt", "nvd")],
+        }));
+        assert_eq!(three_wire_cut(&example())?.wires(), "bvb/cmg, hfx/pzl, jqt/nvd");
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_generate() -> Result<()> {
+        // The generated graph is two groups of 20 joined by three wires.
+        let input = Input::normalize(generate(20, 3));
+        assert_eq!(part1(&input)?, 20 * 20);
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use util::rng::Rng;
+
+const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+/// Calibration lines of letters, digits and spelled-out digits. Every line has at least one digit.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        let digit_at = rng.below(4);
+        for i in 0..4 {
+            if i == digit_at || rng.chance(1, 4) {
+                line.push((b'1' + rng.below(9) as u8) as char);
+            }
+            if rng.chance(1, 2) {
+                let word = rng.pick(&WORDS[..]);
+                line.push_str(word);
+            }
+            for _ in 0..rng.below(3) {
+                line.push((b'a' + rng.below(26) as u8) as char);
+            }
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
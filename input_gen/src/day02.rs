@@ -0,0 +1,32 @@
+use util::rng::Rng;
+
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+/// Games of one to six picks, each showing one to three colours of up to 20 cubes. Like the puzzle
+/// input, every colour shows up somewhere in every game.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for id in 1..=size {
+        let mut picks = (0..rng.range(1, 6))
+            .map(|_| {
+                let mut colors = COLORS.to_vec();
+                rng.shuffle(&mut colors);
+                colors.truncate(rng.range(1, 3) as usize);
+                colors
+            })
+            .collect::<Vec<_>>();
+        for color in COLORS {
+            if !picks.iter().any(|pick| pick.contains(&color)) {
+                let idx = rng.below(picks.len() as u64) as usize;
+                picks[idx].push(color);
+            }
+        }
+        let picks = picks
+            .iter()
+            .map(|colors| colors.iter().map(|color| format!("{} {}", rng.range(1, 20), color)).collect::<Vec<_>>().join(", "))
+            .collect::<Vec<_>>();
+        out.push_str(&format!("Game {}: {}\n", id, picks.join("; ")));
+    }
+    out
+}
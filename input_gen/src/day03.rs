@@ -0,0 +1,29 @@
+use util::rng::Rng;
+
+const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+
+/// A square schematic of the given size with part numbers of one to three digits and symbols
+/// scattered between them. Numbers on a row are always separated by a non-digit.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut row = Vec::with_capacity(size);
+        while row.len() < size {
+            let digits = rng.range(1, 3) as usize;
+            if rng.chance(1, 4) && row.len() + digits < size && !row.last().is_some_and(u8::is_ascii_digit) {
+                let number = rng.range(10i64.pow(digits as u32 - 1).max(1), 10i64.pow(digits as u32) - 1);
+                row.extend(number.to_string().bytes());
+                row.push(b'.');
+            } else if rng.chance(1, 6) {
+                row.push(*rng.pick(SYMBOLS));
+            } else {
+                row.push(b'.');
+            }
+        }
+        row.truncate(size);
+        out.push_str(std::str::from_utf8(&row).unwrap());
+        out.push('\n');
+    }
+    out
+}
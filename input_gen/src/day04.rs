@@ -0,0 +1,35 @@
+use util::rng::Rng;
+
+/// The most card copies a generated pile may end up with, so part 2 stays within a u32.
+const MAX_COPIES: u64 = 100_000_000;
+
+/// Cards numbered 1 to `size`, each with 10 winning numbers and 25 numbers on hand from 1 to 99.
+/// No card wins copies of cards past the end, and wins are cut short once the pile would grow too
+/// large.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut copies = vec![1u64; size];
+    let mut total = size as u64;
+    let mut out = String::new();
+    for idx in 0..size {
+        let mut wins = rng.range(0, 10.min(size - 1 - idx) as i64) as usize;
+        if total + copies[idx] * wins as u64 > MAX_COPIES {
+            wins = 0;
+        }
+        total += copies[idx] * wins as u64;
+        for next in idx + 1..=idx + wins {
+            copies[next] += copies[idx];
+        }
+
+        let mut numbers = (1..=99).collect::<Vec<u32>>();
+        rng.shuffle(&mut numbers);
+        let winning = &numbers[..10];
+        let mut on_hand = winning[..wins].to_vec();
+        on_hand.extend(&numbers[10..35 - wins]);
+        rng.shuffle(&mut on_hand);
+
+        let list = |nums: &[u32]| nums.iter().map(|n| format!("{:>2}", n)).collect::<Vec<_>>().join(" ");
+        out.push_str(&format!("Card {:>3}: {} | {}\n", idx + 1, list(winning), list(&on_hand)));
+    }
+    out
+}
@@ -0,0 +1,34 @@
+use util::rng::Rng;
+
+const KINDS: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+
+/// The largest number used in the almanac, like in the puzzle input.
+const LIMIT: u64 = 1 << 32;
+
+/// An almanac with `size` seed ranges and `size` ranges in each of the seven maps, with at least
+/// one of each since the puzzle needs a seed. The source ranges of a map never overlap.
+pub fn generate(size: usize, seed: u64) -> String {
+    let size = size.max(1);
+    let mut rng = Rng::new(seed);
+    let seeds = (0..size)
+        .flat_map(|_| {
+            let start = rng.below(LIMIT / 2);
+            [start, 1 + rng.below(LIMIT / 64)]
+        })
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+    let mut out = format!("seeds: {}\n", seeds.join(" "));
+
+    let slot = LIMIT / size as u64;
+    for kinds in KINDS.windows(2) {
+        out.push_str(&format!("\n{}-to-{} map:\n", kinds[0], kinds[1]));
+        for i in 0..size as u64 {
+            // Each range sits in its own slot of the number line, so they can't overlap.
+            let length = 1 + rng.below(slot / 2);
+            let source = i * slot + rng.below(slot - length);
+            let dest = rng.below(LIMIT - length);
+            out.push_str(&format!("{} {} {}\n", dest, source, length));
+        }
+    }
+    out
+}
@@ -0,0 +1,19 @@
+use util::rng::Rng;
+
+/// Races with two-digit times and three-digit record distances that can always be beaten. The
+/// number of races is capped at six, so the single long race of part 2 still fits in a u64.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let races = (0..size.clamp(1, 6))
+        .map(|_| {
+            let time = rng.range(21, 99);
+            (time, rng.range(100, time * time / 4 - 1))
+        })
+        .collect::<Vec<_>>();
+    let row = |nums: Vec<i64>| nums.iter().map(|n| format!("{:>5}", n)).collect::<String>();
+    format!(
+        "Time:    {}\nDistance:{}\n",
+        row(races.iter().map(|r| r.0).collect()),
+        row(races.iter().map(|r| r.1).collect())
+    )
+}
@@ -0,0 +1,22 @@
+use util::rng::Rng;
+
+const CARDS: &[u8] = b"23456789TJQKA";
+
+/// Hands of five cards with bids of up to 1000. Half the hands repeat a card a few times, so all
+/// hand types show up.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut hand = (0..5).map(|_| *rng.pick(CARDS)).collect::<Vec<_>>();
+        if rng.chance(1, 2) {
+            let card = hand[0];
+            for _ in 0..rng.range(1, 4) {
+                let idx = rng.below(5) as usize;
+                hand[idx] = card;
+            }
+        }
+        out.push_str(&format!("{} {}\n", String::from_utf8(hand).unwrap(), rng.range(1, 1000)));
+    }
+    out
+}
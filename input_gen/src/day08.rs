@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+
+use util::rng::Rng;
+
+const PRIMES: [usize; 10] = [11, 13, 17, 19, 23, 29, 31, 37, 41, 43];
+
+/// A random three letter name that isn't taken yet, ending in `last` if given and otherwise in
+/// neither A nor Z.
+fn name(rng: &mut Rng, taken: &mut HashSet<String>, last: Option<u8>) -> String {
+    loop {
+        let mut name = (0..3).map(|_| b'A' + rng.below(26) as u8).collect::<Vec<_>>();
+        match last {
+            Some(last) => name[2] = last,
+            None if matches!(name[2], b'A' | b'Z') => continue,
+            None => {}
+        }
+        let name = String::from_utf8(name).unwrap();
+        if taken.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// A network with `size` instructions (at most 20) and one to six ghosts, the first starting at
+/// AAA and ending at ZZZ. Like in the puzzle input, each ghost walks a loop that reaches its end
+/// node after a prime multiple of the instruction count and then starts over, so the ghosts meet
+/// after the least common multiple of those.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let instructions = (0..size.clamp(1, 20)).map(|_| if rng.chance(1, 2) { 'L' } else { 'R' }).collect::<Vec<_>>();
+    let mut primes = PRIMES.to_vec();
+    rng.shuffle(&mut primes);
+
+    let mut taken = HashSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut loops = vec![];
+    for (ghost, prime) in primes.iter().take(rng.range(1, 6) as usize).enumerate() {
+        let length = instructions.len() * prime;
+        let mut nodes = vec![];
+        nodes.push(if ghost == 0 { "AAA".to_string() } else { name(&mut rng, &mut taken, Some(b'A')) });
+        for _ in 1..length {
+            nodes.push(name(&mut rng, &mut taken, None));
+        }
+        nodes.push(if ghost == 0 { "ZZZ".to_string() } else { name(&mut rng, &mut taken, Some(b'Z')) });
+        loops.push(nodes);
+    }
+
+    let all = loops.iter().flatten().cloned().collect::<Vec<_>>();
+    let mut lines = vec![];
+    for nodes in loops.iter() {
+        // The node after step s is taken with instruction s, which repeats exactly once per loop.
+        for (step, node) in nodes.iter().enumerate() {
+            let next = if step + 1 == nodes.len() { &nodes[1] } else { &nodes[step + 1] };
+            let other = rng.pick(&all);
+            let (left, right) = if instructions[step % instructions.len()] == 'L' { (next, other) } else { (other, next) };
+            lines.push(format!("{} = ({}, {})", node, left, right));
+        }
+    }
+    rng.shuffle(&mut lines);
+    format!("{}\n\n{}\n", instructions.iter().collect::<String>(), lines.join("\n"))
+}
@@ -0,0 +1,17 @@
+use util::rng::Rng;
+
+/// `size` histories of 5 to 21 values, each sampled from a random polynomial of degree up to 5
+/// with small integer coefficients.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..size {
+        let coefficients = (0..=rng.range(0, 5)).map(|_| rng.range(-5, 5)).collect::<Vec<_>>();
+        let values = (0..rng.range(5, 21))
+            .map(|x| coefficients.iter().rev().fold(0, |acc, c| acc * x + c).to_string())
+            .collect::<Vec<_>>();
+        out.push_str(&values.join(" "));
+        out.push('\n');
+    }
+    out
+}
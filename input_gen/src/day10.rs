@@ -0,0 +1,60 @@
+use util::rng::Rng;
+
+/// The pipe connecting the directions towards two neighbours, each given as (dx, dy).
+fn pipe(a: (i64, i64), b: (i64, i64)) -> u8 {
+    let has = |d: (i64, i64)| a == d || b == d;
+    match (has((0, -1)), has((0, 1)), has((-1, 0)), has((1, 0))) {
+        (true, true, _, _) => b'|',
+        (_, _, true, true) => b'-',
+        (true, _, _, true) => b'L',
+        (true, _, true, _) => b'J',
+        (_, true, true, _) => b'7',
+        _ => b'F',
+    }
+}
+
+/// A map of (size + 1) x (size + 1) tiles. The loop runs around a random skyline: columns of
+/// random heights standing on the bottom edge, which always outline a single closed loop. S is
+/// placed somewhere on the loop and every other tile is junk pipe or ground, except next to S,
+/// where junk could connect to it.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let n = size.max(1) as i64;
+    let heights = (0..n).map(|_| rng.range(1, n)).collect::<Vec<_>>();
+
+    // Walk the outline corner by corner: up the left edge, along the tops of the columns, down
+    // the right edge and back along the bottom.
+    let mut corners = vec![(0, n), (0, n - heights[0])];
+    for x in 0..n {
+        corners.push((x + 1, n - heights[x as usize]));
+        corners.push((x + 1, n - heights.get(x as usize + 1).copied().unwrap_or(0)));
+    }
+    corners.push((0, n));
+    let mut path: Vec<(i64, i64)> = vec![];
+    for w in corners.windows(2) {
+        let (mut pos, to) = (w[0], w[1]);
+        while pos != to {
+            path.push(pos);
+            pos = (pos.0 + (to.0 - pos.0).signum(), pos.1 + (to.1 - pos.1).signum());
+        }
+    }
+
+    let side = (n + 1) as usize;
+    let junk = b"|-LJ7F.......";
+    let mut grid = (0..side).map(|_| (0..side).map(|_| *rng.pick(junk)).collect::<Vec<_>>()).collect::<Vec<_>>();
+    let len = path.len();
+    for (i, pos) in path.iter().enumerate() {
+        let (prev, next) = (path[(i + len - 1) % len], path[(i + 1) % len]);
+        grid[pos.1 as usize][pos.0 as usize] = pipe((prev.0 - pos.0, prev.1 - pos.1), (next.0 - pos.0, next.1 - pos.1));
+    }
+    let start = path[rng.below(len as u64) as usize];
+    for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+        let (x, y) = (start.0 + dx, start.1 + dy);
+        if (0..=n).contains(&x) && (0..=n).contains(&y) && !path.contains(&(x, y)) {
+            grid[y as usize][x as usize] = b'.';
+        }
+    }
+    grid[start.1 as usize][start.0 as usize] = b'S';
+
+    grid.into_iter().map(|row| String::from_utf8(row).unwrap() + "\n").collect()
+}
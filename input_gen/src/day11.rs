@@ -0,0 +1,18 @@
+use util::rng::Rng;
+
+/// A square image of the given size with galaxies on about one in twenty cells, leaving some rows
+/// and columns empty so that the expansion matters.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let empty_rows = (0..size).map(|_| rng.chance(1, 8)).collect::<Vec<_>>();
+    let empty_columns = (0..size).map(|_| rng.chance(1, 8)).collect::<Vec<_>>();
+    let mut out = String::new();
+    for empty_row in empty_rows.iter() {
+        for empty_column in empty_columns.iter() {
+            let galaxy = !empty_row && !empty_column && rng.chance(1, 20);
+            out.push(if galaxy { '#' } else { '.' });
+        }
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,26 @@
+use util::rng::Rng;
+
+/// `size` condition records of 3 to 12 springs. Each record is made from an actual row of springs
+/// with at least one damaged spring, then about half of the conditions are hidden behind a `?`.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..size {
+        let len = rng.range(3, 12) as usize;
+        let mut springs = (0..len).map(|_| rng.chance(1, 2)).collect::<Vec<_>>();
+        let idx = rng.below(len as u64) as usize;
+        springs[idx] = true;
+
+        let groups = springs
+            .split(|damaged| !damaged)
+            .filter(|group| !group.is_empty())
+            .map(|group| group.len().to_string())
+            .collect::<Vec<_>>();
+        let conditions = springs
+            .iter()
+            .map(|damaged| if rng.chance(1, 2) { '?' } else if *damaged { '#' } else { '.' })
+            .collect::<String>();
+        out.push_str(&format!("{} {}\n", conditions, groups.join(",")));
+    }
+    out
+}
@@ -0,0 +1,69 @@
+use util::rng::Rng;
+
+/// The number of mirror positions among the items where the two sides differ in exactly
+/// `smudges` cells.
+fn mirrors(items: &[Vec<bool>], smudges: usize) -> usize {
+    (1..items.len())
+        .filter(|before| {
+            let diff: usize = (0..*before.min(&(items.len() - before)))
+                .map(|i| items[before - 1 - i].iter().zip(items[before + i].iter()).filter(|(a, b)| a != b).count())
+                .sum();
+            diff == smudges
+        })
+        .count()
+}
+
+fn transpose(cells: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    (0..cells[0].len()).map(|x| cells.iter().map(|row| row[x]).collect()).collect()
+}
+
+/// One pattern with exactly one clean mirror and exactly one mirror with a single smudge. The
+/// pattern is made symmetric around a row line and a column line, then a cell outside the rows
+/// the row mirror covers is flipped, which smudges the column mirror only.
+fn pattern(rng: &mut Rng) -> Vec<Vec<bool>> {
+    loop {
+        let (height, width) = (rng.range(5, 17) as usize, rng.range(5, 17) as usize);
+        let mut cells = (0..height).map(|_| (0..width).map(|_| rng.chance(1, 2)).collect::<Vec<_>>()).collect::<Vec<_>>();
+
+        let row_line = rng.range(1, height as i64 - 1) as usize;
+        let row_reach = row_line.min(height - row_line);
+        if 2 * row_reach == height {
+            continue;
+        }
+        for i in 0..row_reach {
+            cells[row_line + i] = cells[row_line - 1 - i].clone();
+        }
+        let column_line = rng.range(1, width as i64 - 1) as usize;
+        let column_reach = column_line.min(width - column_line);
+        for row in cells.iter_mut() {
+            for i in 0..column_reach {
+                row[column_line + i] = row[column_line - 1 - i];
+            }
+        }
+
+        let outside = (0..height).filter(|y| *y + row_reach < row_line || *y >= row_line + row_reach).collect::<Vec<_>>();
+        let y = *rng.pick(&outside);
+        let x = column_line + rng.below(column_reach as u64) as usize;
+        cells[y][x] = !cells[y][x];
+
+        let columns = transpose(&cells);
+        let count = |smudges| mirrors(&cells, smudges) + mirrors(&columns, smudges);
+        if count(0) == 1 && count(1) == 1 {
+            return if rng.chance(1, 2) { columns } else { cells };
+        }
+    }
+}
+
+/// `size` patterns of 5 to 17 rows and columns, each with one clean and one smudged mirror.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    (0..size)
+        .map(|_| {
+            pattern(&mut rng)
+                .iter()
+                .map(|row| row.iter().map(|rock| if *rock { '#' } else { '.' }).collect::<String>() + "\n")
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
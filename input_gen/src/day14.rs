@@ -0,0 +1,19 @@
+use util::rng::Rng;
+
+/// A square platform of the given size with about one round rock in five cells and one cube rock
+/// in six.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..size {
+        for _ in 0..size {
+            out.push(match rng.below(30) {
+                0..=5 => 'O',
+                6..=10 => '#',
+                _ => '.',
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
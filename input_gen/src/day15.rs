@@ -0,0 +1,17 @@
+use util::rng::Rng;
+
+/// A sequence of `size` steps on a handful of labels of two to six letters, so that lenses get
+/// both replaced and removed. About a third of the steps remove a lens.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let labels = (0..(size / 4).max(3))
+        .map(|_| (0..rng.range(2, 6)).map(|_| (b'a' + rng.below(26) as u8) as char).collect::<String>())
+        .collect::<Vec<_>>();
+    let steps = (0..size)
+        .map(|_| {
+            let label = rng.pick(&labels);
+            if rng.chance(1, 3) { format!("{}-", label) } else { format!("{}={}", label, rng.range(1, 9)) }
+        })
+        .collect::<Vec<_>>();
+    steps.join(",") + "\n"
+}
@@ -0,0 +1,25 @@
+use util::rng::Rng;
+
+/// A square garden of 2 * size + 1 plots with S in the middle and rocks on about one plot in
+/// eight. Like the puzzle input, the middle row and column and the edges are clear of rocks, which
+/// is what makes the reachable count on the tiled map grow quadratically.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let side = 2 * size.max(2) + 1;
+    let middle = side / 2;
+    let mut out = String::new();
+    for y in 0..side {
+        for x in 0..side {
+            let clear = x == middle || y == middle || x == 0 || y == 0 || x == side - 1 || y == side - 1;
+            out.push(if (x, y) == (middle, middle) {
+                'S'
+            } else if !clear && rng.chance(1, 8) {
+                '#'
+            } else {
+                '.'
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,28 @@
+use util::rng::Rng;
+
+/// `size` bricks of one to four cubes in a 10 x 10 column. The bricks are stacked one by one on
+/// top of whatever is below them, with a random gap, so no two bricks ever overlap. They are
+/// listed in random order.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut top = [[0u64; 10]; 10];
+    let mut bricks = vec![];
+    for _ in 0..size {
+        let length = rng.range(0, 3) as u64;
+        let axis = rng.below(3);
+        let (dx, dy, dz) = match axis {
+            0 => (length, 0, 0),
+            1 => (0, length, 0),
+            _ => (0, 0, length),
+        };
+        let (x, y) = (rng.below(10 - dx), rng.below(10 - dy));
+        let below = (x..=x + dx).flat_map(|x| (y..=y + dy).map(move |y| (x, y))).map(|(x, y)| top[x as usize][y as usize]).max().unwrap();
+        let z = below + 1 + rng.below(4);
+        for (x, y) in (x..=x + dx).flat_map(|x| (y..=y + dy).map(move |y| (x, y))) {
+            top[x as usize][y as usize] = z + dz;
+        }
+        bricks.push(format!("{},{},{}~{},{},{}", x, y, z, x + dx, y + dy, z + dz));
+    }
+    rng.shuffle(&mut bricks);
+    bricks.iter().map(|brick| brick.clone() + "\n").collect()
+}
@@ -0,0 +1,46 @@
+use util::rng::Rng;
+
+/// A trail map shaped like the puzzle input: a lattice of size x size junctions (at most 6 x 6,
+/// which the longest path search handles) joined by straight paths of random lengths. Some paths
+/// are left out, but every junction keeps a path from the left or from above, so the end can be
+/// reached. Slopes at both ends of each path only allow walking right and down.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let k = size.clamp(1, 6);
+    let positions = |rng: &mut Rng| {
+        let mut at = 1;
+        (0..k)
+            .map(|_| {
+                at += rng.range(4, 10) as usize;
+                at
+            })
+            .collect::<Vec<_>>()
+    };
+    let xs = positions(&mut rng);
+    let ys = positions(&mut rng);
+    let (width, height) = (xs[k - 1] + 6, ys[k - 1] + 6);
+    let mut grid = vec![vec![b'#'; width]; height];
+
+    for j in 0..k {
+        for i in 0..k {
+            let (x, y) = (xs[i], ys[j]);
+            grid[y][x] = b'.';
+            let from_left = i > 0 && (j == 0 || rng.chance(3, 4));
+            let from_above = j > 0 && (i == 0 || !from_left || rng.chance(3, 4));
+            if from_left {
+                (xs[i - 1] + 1..x).for_each(|x| grid[y][x] = b'.');
+                grid[y][xs[i - 1] + 1] = b'>';
+                grid[y][x - 1] = b'>';
+            }
+            if from_above {
+                (ys[j - 1] + 1..y).for_each(|y| grid[y][x] = b'.');
+                grid[ys[j - 1] + 1][x] = b'v';
+                grid[y - 1][x] = b'v';
+            }
+        }
+    }
+    (0..ys[0]).for_each(|y| grid[y][xs[0]] = b'.');
+    (ys[k - 1] + 1..height).for_each(|y| grid[y][xs[k - 1]] = b'.');
+
+    grid.into_iter().map(|row| String::from_utf8(row).unwrap() + "\n").collect()
+}
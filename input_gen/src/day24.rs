@@ -0,0 +1,25 @@
+use util::rng::Rng;
+
+/// `size` hailstones (at least three) that a rock thrown from an integer position with an integer
+/// velocity hits one by one, each at its own whole nanosecond. Hailstone positions are spread
+/// around the part 1 test area, and no velocity has a zero component.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let rock_position = (0..3).map(|_| rng.range(200_000_000_000_000, 400_000_000_000_000)).collect::<Vec<_>>();
+    let rock_velocity = (0..3).map(|_| rng.range(-300, 300)).collect::<Vec<_>>();
+    let mut times = vec![];
+    let mut out = String::new();
+    while times.len() < size.max(3) {
+        let time = rng.range(10_000_000_000, 1_000_000_000_000);
+        let velocity = (0..3).map(|_| rng.range(-300, 300)).collect::<Vec<_>>();
+        if times.contains(&time) || velocity.contains(&0) || velocity == rock_velocity {
+            continue;
+        }
+        times.push(time);
+        // Both are at the same place at the time: p + t * v = P + t * V.
+        let position = (0..3).map(|i| rock_position[i] + time * (rock_velocity[i] - velocity[i])).collect::<Vec<_>>();
+        let join = |v: &[i64]| v.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+        out.push_str(&format!("{} @ {}\n", join(&position), join(&velocity)));
+    }
+    out
+}
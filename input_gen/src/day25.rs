@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use util::rng::Rng;
+
+/// Connects each node to the next two around a ring, which takes at least four wires to cut, and
+/// adds a few random extra wires.
+fn ring(rng: &mut Rng, nodes: &[String], wires: &mut Vec<(String, String)>) {
+    let n = nodes.len();
+    for i in 0..n {
+        wires.push((nodes[i].clone(), nodes[(i + 1) % n].clone()));
+        wires.push((nodes[i].clone(), nodes[(i + 2) % n].clone()));
+    }
+    for _ in 0..n / 2 {
+        let (a, b) = (rng.pick(nodes), rng.pick(nodes));
+        let known = wires.iter().any(|(x, y)| (x == a && y == b) || (x == b && y == a));
+        if a != b && !known {
+            wires.push((a.clone(), b.clone()));
+        }
+    }
+}
+
+/// Two groups of `size` components (between 5 and 5000) that are each well connected inside, and
+/// exactly three wires between the groups, so cutting those three is the only way to split the
+/// machine in two.
+pub fn generate(size: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let n = size.clamp(5, 5000);
+    let mut taken = HashSet::new();
+    let mut names = vec![];
+    while names.len() < 2 * n {
+        let name = (0..3).map(|_| (b'a' + rng.below(26) as u8) as char).collect::<String>();
+        if taken.insert(name.clone()) {
+            names.push(name);
+        }
+    }
+    let (left, right) = names.split_at(n);
+
+    let mut wires = vec![];
+    ring(&mut rng, left, &mut wires);
+    ring(&mut rng, right, &mut wires);
+    let mut ends = (left.to_vec(), right.to_vec());
+    rng.shuffle(&mut ends.0);
+    rng.shuffle(&mut ends.1);
+    for i in 0..3 {
+        wires.push((ends.0[i].clone(), ends.1[i].clone()));
+    }
+
+    // Each wire is listed once, on the line of one of its ends.
+    let mut lines: Vec<(String, Vec<String>)> = vec![];
+    for (a, b) in wires {
+        let (from, to) = if rng.chance(1, 2) { (a, b) } else { (b, a) };
+        match lines.iter_mut().find(|(name, _)| *name == from) {
+            Some((_, tos)) => tos.push(to),
+            None => lines.push((from, vec![to])),
+        }
+    }
+    rng.shuffle(&mut lines);
+    lines.iter().map(|(from, tos)| format!("{}: {}\n", from, tos.join(" "))).collect()
+}
@@ -0,0 +1,112 @@
+//! Random puzzle inputs for every day. Each day's module has a `generate(size, seed)` that
+//! always gives the same input for the same arguments, and that day's solution accepts.
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+pub type Generator = fn(usize, u64) -> String;
+
+/// The generators by day.
+pub const GENERATORS: [(u32, Generator); 20] = [
+    (1, day01::generate),
+    (2, day02::generate),
+    (3, day03::generate),
+    (4, day04::generate),
+    (5, day05::generate),
+    (6, day06::generate),
+    (7, day07::generate),
+    (8, day08::generate),
+    (9, day09::generate),
+    (10, day10::generate),
+    (11, day11::generate),
+    (12, day12::generate),
+    (13, day13::generate),
+    (14, day14::generate),
+    (15, day15::generate),
+    (21, day21::generate),
+    (22, day22::generate),
+    (23, day23::generate),
+    (24, day24::generate),
+    (25, day25::generate),
+];
+
+pub fn generator(day: u32) -> Option<Generator> {
+    GENERATORS.iter().find(|(d, _)| *d == day).map(|(_, generate)| *generate)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::GENERATORS;
+    use std::collections::HashSet;
+
+    /// The size of a generated input, measured in whatever the day's generator counts.
+    fn measured_size(day: u32, text: &str) -> usize {
+        let lines = text.lines().collect::<Vec<_>>();
+        match day {
+            3 | 11 | 14 => {
+                assert!(lines.iter().all(|line| line.len() == lines.len()), "day {} is not square", day);
+                lines.len()
+            }
+            5 => (lines[0].split_whitespace().count() - 1) / 2,
+            6 => lines[0].split_whitespace().count() - 1,
+            8 => lines[0].len(),
+            10 => lines.len() - 1,
+            13 => text.trim_end().split("\n\n").count(),
+            15 => text.trim_end().split(',').count(),
+            21 => lines.len() / 2,
+            // The first row of junctions is joined left to right, with a slope at both ends of each path.
+            23 => lines.iter().map(|line| line.matches('>').count()).max().unwrap() / 2 + 1,
+            25 => text.split([':', ' ', '\n']).filter(|name| !name.is_empty()).collect::<HashSet<_>>().len() / 2,
+            _ => lines.len(),
+        }
+    }
+
+    /// The requested size after the day's generator has clamped it to what it supports.
+    fn expected_size(day: u32, size: usize) -> usize {
+        match day {
+            5 => size.max(1),
+            6 => size.clamp(1, 6),
+            8 => size.clamp(1, 20),
+            21 => size.max(2),
+            23 => size.clamp(1, 6),
+            24 => size.max(3),
+            25 => size.clamp(5, 5000),
+            _ => size,
+        }
+    }
+
+    #[test]
+    pub fn test_reproducible() {
+        for (day, generate) in GENERATORS {
+            assert_eq!(generate(8, 1), generate(8, 1), "day {}", day);
+            assert_ne!(generate(8, 1), generate(8, 2), "day {}", day);
+        }
+    }
+
+    #[test]
+    pub fn test_size() {
+        for (day, generate) in GENERATORS {
+            for size in [1, 4, 9] {
+                assert_eq!(measured_size(day, &generate(size, 1)), expected_size(day, size), "day {} size {}", day, size);
+            }
+        }
+    }
+}
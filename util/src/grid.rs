@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::Input;
+
+/// A position in a grid as (x, y).
+pub type Pos = (usize, usize);
+
+/// A rectangular grid of chars.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+    cells: Vec<Vec<char>>,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// A graph where the nodes are the junctions of a grid and the edges are the corridors between
+/// them, weighted by corridor length.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JunctionGraph {
+    /// The grid position of each node.
+    pub nodes: Vec<Pos>,
+    /// For each node, the (node index, length) pairs reachable through a corridor.
+    pub edges: Vec<Vec<(usize, u32)>>,
+}
+
+/// Why some input isn't a well-formed grid. Line and column numbers are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    Ragged { line: usize, expected: usize, found: usize },
+    Unexpected { line: usize, column: usize, ch: char },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "the grid is empty"),
+            GridError::Ragged { line, expected, found } =>
+                write!(f, "line {}: expected a row of width {}, found width {}", line, expected, found),
+            GridError::Unexpected { line, column, ch } =>
+                write!(f, "line {}, column {}: unexpected character {:?}", line, column, ch),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl Grid {
+    pub fn from_input(input: &Input) -> Grid {
+        let cells: Vec<Vec<char>> = input.as_lines().map(|line| line.chars().collect()).collect();
+        let height = cells.len();
+        let width = cells.first().map(|row| row.len()).unwrap_or(0);
+        Grid { cells, width, height }
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<char> {
+        self.cells.get(pos.1).and_then(|row| row.get(pos.0)).copied()
+    }
+
+    /// Finds the first position (in reading order) containing the given char.
+    pub fn find(&self, ch: char) -> Option<Pos> {
+        self.positions().find(|pos| self.get(*pos) == Some(ch))
+    }
+
+    /// Iterates over all positions in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+    }
+
+    /// Returns the orthogonal neighbours of a position that lie within the grid.
+    pub fn neighbours(&self, pos: Pos) -> Vec<Pos> {
+        let (x, y) = pos;
+        let mut ns = Vec::with_capacity(4);
+        if y > 0 { ns.push((x, y - 1)); }
+        if x + 1 < self.width { ns.push((x + 1, y)); }
+        if y + 1 < self.height { ns.push((x, y + 1)); }
+        if x > 0 { ns.push((x - 1, y)); }
+        ns
+    }
+}
+
+/// Compresses a grid into a weighted graph of its junctions. A junction is a position connected to
+/// three or more neighbours, or one of the given extra positions (typically start and end).
+/// `can_step(grid, from, to)` decides if a single step is allowed. It may be directional, but must
+/// reject steps from or to a blocked position.
+pub fn junction_graph<F>(grid: &Grid, extra: &[Pos], can_step: F) -> JunctionGraph
+where
+    F: Fn(&Grid, Pos, Pos) -> bool,
+{
+    let is_junction = |pos: Pos| {
+        let connected = grid.neighbours(pos).into_iter().filter(|n| can_step(grid, pos, *n) || can_step(grid, *n, pos));
+        connected.count() >= 3
+    };
+    let nodes: Vec<Pos> = grid.positions().filter(|pos| extra.contains(pos) || is_junction(*pos)).collect();
+    let index: HashMap<Pos, usize> = nodes.iter().enumerate().map(|(idx, pos)| (*pos, idx)).collect();
+
+    let edges = nodes
+        .iter()
+        .map(|junction| {
+            grid.neighbours(*junction)
+                .into_iter()
+                .filter(|n| can_step(grid, *junction, *n))
+                .filter_map(|first| {
+                    // Follow the corridor until reaching another junction or a dead end.
+                    let mut prev = *junction;
+                    let mut current = first;
+                    let mut length = 1;
+                    while !index.contains_key(&current) {
+                        let next = grid
+                            .neighbours(current)
+                            .into_iter()
+                            .find(|n| *n != prev && can_step(grid, current, *n))?;
+                        prev = current;
+                        current = next;
+                        length += 1;
+                    }
+                    Some((index[&current], length))
+                })
+                .collect()
+        })
+        .collect();
+
+    JunctionGraph { nodes, edges }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::{junction_graph, Grid, GridError};
+    use crate::Input;
+
+    #[test]
+    pub fn test_junction_graph() {
+        let grid = Grid::from_input(&Input::from_lines([
+            "#.###",
+            "#...#",
+            "#.#.#",
+            "#...#",
+            "###.#",
+        ]));
+        let graph = junction_graph(&grid, &[(1, 0), (3, 4)], |g, from, to| g.get(from) != Some('#') && g.get(to) != Some('#'));
+        assert_eq!(graph.nodes, vec![(1, 0), (1, 1), (3, 3), (3, 4)]);
+        assert_eq!(graph.edges[0], vec![(1, 1)]);
+        assert_eq!(graph.edges[1], vec![(0, 1), (2, 4), (2, 4)]);
+        assert_eq!(graph.edges[3], vec![(2, 1)]);
+    }
+
+    #[test]
+    pub fn test_validate_grid() {
+        assert_eq!(Input::from_lines(["#.#", "...", "#.#"]).validate_grid(".#"), Ok((3, 3)));
+        assert_eq!(Input::from_lines(["#.#", "..", "#.#"]).validate_grid(".#"),
+                   Err(GridError::Ragged { line: 2, expected: 3, found: 2 }));
+        assert_eq!(Input::from_lines(["#.#", ".O.", "#.#"]).validate_grid(".#"),
+                   Err(GridError::Unexpected { line: 2, column: 2, ch: 'O' }));
+        assert_eq!(Input::from_str("").validate_grid(".#"), Err(GridError::Empty));
+    }
+}
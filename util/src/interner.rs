@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+/// Assigns dense ids to names, in order of first appearance, so that puzzles with string-named
+/// nodes can index into plain vectors instead of looking names up on every step.
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// Returns the id of the name, assigning the next free id if it hasn't been seen before.
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len() as u32;
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        id
+    }
+
+    /// Returns the id of an already interned name.
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterates over (id, name) pairs in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.names.iter().enumerate().map(|(id, name)| (id as u32, name.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Interner;
+
+    #[test]
+    pub fn test_intern() {
+        let mut interner = Interner::new();
+        assert_eq!(interner.intern("AAA"), 0);
+        assert_eq!(interner.intern("BBB"), 1);
+        assert_eq!(interner.intern("AAA"), 0);
+        assert_eq!(interner.get("BBB"), Some(1));
+        assert_eq!(interner.get("CCC"), None);
+        assert_eq!(interner.name(1), "BBB");
+        assert_eq!(interner.len(), 2);
+    }
+}
@@ -1,9 +1,25 @@
-use std::fs::File;
-use std::io::Read;
 use std::path::Path;
 use std::str::Split;
 use std::collections::HashMap;
 
+use grid::GridError;
+use parse::ParseError;
+
+pub mod grid;
+mod interner;
+pub mod math;
+pub mod parse;
+pub mod poly;
+pub mod rng;
+pub mod scan;
+pub mod search;
+pub mod viz;
+
+pub use interner::Interner;
+pub use parse::FromInput;
+pub use scan::numbers;
+pub use util_derive::FromInput;
+
 #[derive(Clone)]
 pub struct Input(String);
 
@@ -14,15 +30,13 @@ impl Input {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        Input(
-            lines
-                .into_iter()
-                .fold(String::new(), |complete, line| {
-                    complete + line.as_ref() + "\n"
-                })
-                .trim_end()
-                .to_string(),
-        )
+        let mut text = String::new();
+        for line in lines {
+            text.push_str(line.as_ref());
+            text.push('\n');
+        }
+        text.truncate(text.trim_end().len());
+        Input(text)
     }
 
     pub fn from_str(input: impl Into<String>) -> Input {
@@ -34,10 +48,22 @@ impl Input {
         Input(self.0.trim_end_matches('\n').to_string())
     }
 
+    /// Load the input from a file, reading the bytes once and validating them as UTF-8 in place.
+    /// The text is normalized, see [Input::normalize].
     pub fn load(path: impl AsRef<Path>) -> std::io::Result<Input> {
-        let mut input = String::new();
-        File::open(path)?.read_to_string(&mut input)?;
-        Ok(Input(input))
+        let bytes = std::fs::read(path)?;
+        let text = String::from_utf8(bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(Input::normalize(text))
+    }
+
+    /// Strip a byte order mark and turn CRLF line endings into LF, so that files edited on Windows
+    /// behave like the original input. Other whitespace is kept, since some maps use spaces.
+    pub fn normalize(text: String) -> Input {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+        if !text.contains('\r') {
+            return Input(text.to_string());
+        }
+        Input(text.replace("\r\n", "\n"))
     }
 
     /// Get the input as a string
@@ -45,10 +71,144 @@ impl Input {
         &self.0
     }
 
+    /// Get the input as raw bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
     /// Get the input as an iterator of lines
     pub fn as_lines(&self) -> Split<char> {
         self.0.trim_end().split('\n')
     }
+
+    /// Get the input as an iterator of lines of raw bytes, for parsing without allocating
+    pub fn lines_bytes(&self) -> impl Iterator<Item = &[u8]> {
+        self.0.trim_end().as_bytes().split(|b| *b == b'\n')
+    }
+
+    /// Scan all integers in the whole input, see [scan::numbers]
+    pub fn numbers<T: scan::Integer>(&self) -> scan::Numbers<'_, T> {
+        scan::numbers(self.as_bytes())
+    }
+
+    /// Parse every line, reporting the line number of the first line that fails
+    pub fn parse_lines<T: FromInput>(&self) -> Result<Vec<T>, ParseError> {
+        self.as_block().parse_lines()
+    }
+
+    /// Check that the input is a rectangular grid containing only chars from the alphabet,
+    /// returning its (width, height)
+    pub fn validate_grid(&self, alphabet: &str) -> Result<(usize, usize), GridError> {
+        self.as_block().validate_grid(alphabet)
+    }
+
+    /// Get the whole input as a single block
+    pub fn as_block(&self) -> Block<'_> {
+        Block { text: self.0.trim_end(), start_line: 1 }
+    }
+
+    /// Get the blocks of lines that are separated by blank lines
+    pub fn blocks(&self) -> Vec<Block<'_>> {
+        let text = self.0.trim_end();
+        let mut blocks = Vec::new();
+        let mut current: Option<(usize, usize)> = None; // (start offset, start line)
+        let mut offset = 0;
+
+        for (idx, line) in text.split('\n').enumerate() {
+            let blank = line.trim().is_empty();
+            match current {
+                Some((start, start_line)) if blank => {
+                    blocks.push(Block { text: &text[start..offset - 1], start_line });
+                    current = None;
+                }
+                None if !blank => current = Some((offset, idx + 1)),
+                _ => {}
+            }
+            offset += line.len() + 1;
+        }
+        if let Some((start, start_line)) = current {
+            blocks.push(Block { text: &text[start..], start_line });
+        }
+
+        blocks
+    }
+
+    /// Split a "header line, blank line, body" layout into the header and the body
+    pub fn header_and_body(&self) -> Option<(Block<'_>, Block<'_>)> {
+        let text = self.0.trim_end();
+        let (header, rest) = text.split_once('\n')?;
+        let (blank, body) = rest.split_once('\n')?;
+        if !blank.trim().is_empty() {
+            return None;
+        }
+        Some((Block { text: header, start_line: 1 }, Block { text: body, start_line: 3 }))
+    }
+}
+
+/// A borrowed part of the input, remembering the (1-based) line of the input it starts at so that
+/// errors can point at the right place in the original file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Block<'a> {
+    text: &'a str,
+    start_line: usize,
+}
+
+impl<'a> Block<'a> {
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn as_lines(&self) -> Split<'a, char> {
+        self.text.split('\n')
+    }
+
+    /// Scan all integers in the block, see [scan::numbers]
+    pub fn numbers<T: scan::Integer>(&self) -> scan::Numbers<'a, T> {
+        scan::numbers(self.text.as_bytes())
+    }
+
+    /// The line number in the input of the first line in the block
+    pub fn start_line(&self) -> usize {
+        self.start_line
+    }
+
+    /// Iterate over the lines together with their line numbers in the input
+    pub fn numbered_lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let start_line = self.start_line;
+        self.as_lines().enumerate().map(move |(idx, line)| (start_line + idx, line))
+    }
+
+    /// Parse every line, reporting the input line number of the first line that fails
+    pub fn parse_lines<T: FromInput>(&self) -> Result<Vec<T>, ParseError> {
+        self.numbered_lines()
+            .map(|(line_no, line)| T::from_line(line).map_err(|e| e.at_line(line_no)))
+            .collect()
+    }
+
+    /// Check that the block is a rectangular grid containing only chars from the alphabet,
+    /// returning its (width, height)
+    pub fn validate_grid(&self, alphabet: &str) -> Result<(usize, usize), GridError> {
+        if self.text.is_empty() {
+            return Err(GridError::Empty);
+        }
+        let mut width = None;
+        let mut height = 0;
+        for (line_no, line) in self.numbered_lines() {
+            let mut found = 0;
+            for (idx, ch) in line.chars().enumerate() {
+                if !alphabet.contains(ch) {
+                    return Err(GridError::Unexpected { line: line_no, column: idx + 1, ch });
+                }
+                found += 1;
+            }
+            let expected = *width.get_or_insert(found);
+            if found != expected {
+                return Err(GridError::Ragged { line: line_no, expected, found });
+            }
+            height += 1;
+        }
+        Ok((width.unwrap_or(0), height))
+    }
 }
 
 /// Works like GroupBy in C#; groups items by an item-derived key, associating a list of item-derived values with each key.
@@ -68,4 +228,34 @@ pub fn satisfies<T, P>(opt: Option<T>, predicate: P) -> bool where P: Fn(T) -> b
         Some(t) => predicate(t),
         None => false,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Input;
+
+    #[test]
+    pub fn test_blocks() {
+        let input = Input::from_lines(["a", "b", "", "", "c", "", "d", "e"]);
+        let blocks = input.blocks();
+        assert_eq!(blocks.iter().map(|b| b.as_str()).collect::<Vec<_>>(), vec!["a\nb", "c", "d\ne"]);
+        assert_eq!(blocks.iter().map(|b| b.start_line()).collect::<Vec<_>>(), vec![1, 5, 7]);
+    }
+
+    #[test]
+    pub fn test_normalize() {
+        let input = Input::normalize("\u{feff}#.# \r\n...\r\n\r\n".to_string());
+        assert_eq!(input.as_str(), "#.# \n...\n\n");
+        assert_eq!(input.as_lines().collect::<Vec<_>>(), vec!["#.# ", "..."]);
+        assert_eq!(Input::normalize("a\n\nb".to_string()).as_str(), "a\n\nb");
+    }
+
+    #[test]
+    pub fn test_header_and_body() {
+        let input = Input::from_lines(["LR", "", "AAA", "BBB"]);
+        let (header, body) = input.header_and_body().unwrap();
+        assert_eq!(header.as_str(), "LR");
+        assert_eq!(body.numbered_lines().collect::<Vec<_>>(), vec![(3, "AAA"), (4, "BBB")]);
+        assert!(Input::from_lines(["LR", "AAA"]).header_and_body().is_none());
+    }
+}
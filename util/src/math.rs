@@ -0,0 +1,184 @@
+/// Greatest common divisor, gcd(0, 0) is 0.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or None if it overflows. lcm(0, n) is 0.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Greatest common divisor of all the numbers, 0 for an empty slice.
+pub fn gcd_all(nums: &[u64]) -> u64 {
+    nums.iter().fold(0, |acc, n| gcd(acc, *n))
+}
+
+/// Least common multiple of all the numbers, 1 for an empty slice, or None if it overflows.
+pub fn lcm_all(nums: &[u64]) -> Option<u64> {
+    nums.iter().try_fold(1, |acc, n| lcm(acc, *n))
+}
+
+/// Extended Euclid: returns (g, x, y) with a*x + b*y = g = gcd(a, b) and g >= 0.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of a modulo m, in 0..m, if a and m are coprime.
+pub fn mod_inv(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = ext_gcd(a as i128, m as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m as i128) as i64)
+}
+
+/// base^exp modulo m, without overflowing for any u64 arguments, or None if m is 0.
+pub fn pow_mod(base: u64, exp: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    if m == 1 {
+        return Some(0);
+    }
+    let m = m as u128;
+    let (mut base, mut exp, mut result) = (base as u128 % m, exp, 1u128);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    Some(result as u64)
+}
+
+/// Chinese remainder theorem for moduli that need not be coprime. Each congruence is
+/// (residue, modulus) with a positive modulus. Returns the combined (residue, modulus) with the
+/// residue in 0..modulus, or None if the congruences contradict each other or the combined
+/// modulus doesn't fit in an i64.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut acc: (i128, i128) = (0, 1);
+    for (r, m) in congruences.iter() {
+        if *m <= 0 {
+            return None;
+        }
+        let (r1, m1) = acc;
+        let (r2, m2) = ((*r as i128).rem_euclid(*m as i128), *m as i128);
+        let (g, p, _) = ext_gcd(m1, m2);
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+        let modulus = (m1 / g).checked_mul(m2)?;
+        if modulus > i64::MAX as i128 {
+            return None;
+        }
+        // x = r1 + m1 * k, where k = (r2 - r1) / g * p (mod m2 / g).
+        let k = ((r2 - r1) / g % (m2 / g) * p).rem_euclid(m2 / g);
+        acc = ((r1 + m1 * k).rem_euclid(modulus), modulus);
+    }
+    Some((acc.0 as i64, acc.1 as i64))
+}
+
+/// Sum of all the numbers, or None if any partial sum overflows.
+pub fn checked_sum(nums: &[i128]) -> Option<i128> {
+    nums.iter().try_fold(0i128, |acc, n| acc.checked_add(*n))
+}
+
+/// Product of all the numbers, or None if any partial product overflows.
+pub fn checked_product(nums: &[i128]) -> Option<i128> {
+    nums.iter().try_fold(1i128, |acc, n| acc.checked_mul(*n))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{checked_product, checked_sum, crt, ext_gcd, gcd, gcd_all, lcm, lcm_all, mod_inv, pow_mod};
+    use crate::rng::Rng;
+
+    #[test]
+    pub fn test_gcd_lcm_properties() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let (a, b) = (rng.below(10_000), rng.below(10_000));
+            let g = gcd(a, b);
+            if g != 0 {
+                assert_eq!(a % g, 0);
+                assert_eq!(b % g, 0);
+                assert_eq!(gcd(a / g, b / g), 1);
+            }
+            assert_eq!(lcm(a, b).unwrap() as u128 * g as u128, a as u128 * b as u128);
+
+            let (eg, x, y) = ext_gcd(a as i128, b as i128);
+            assert_eq!(eg, g as i128);
+            assert_eq!(a as i128 * x + b as i128 * y, eg);
+        }
+        assert_eq!(gcd_all(&[12, 18, 30]), 6);
+        assert_eq!(lcm_all(&[4, 6, 10]), Some(60));
+        assert_eq!(lcm_all(&[]), Some(1));
+        assert_eq!(lcm_all(&[u64::MAX, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    pub fn test_mod_inv_and_pow_mod() {
+        let mut rng = Rng::new(2);
+        for _ in 0..1000 {
+            let m = 1 + rng.below(1000) as i64;
+            let a = rng.below(1000) as i64;
+            match mod_inv(a, m) {
+                Some(inv) => assert_eq!(a * inv % m, 1 % m),
+                None => assert_ne!(gcd(a as u64, m as u64), 1),
+            }
+
+            let (base, exp, m) = (rng.below(100), rng.below(20), rng.below(1000));
+            let expected = (m > 0).then(|| (0..exp).fold(1 % m, |acc, _| acc * base % m));
+            assert_eq!(pow_mod(base, exp, m), expected);
+        }
+        assert_eq!(pow_mod(u64::MAX, u64::MAX, u64::MAX - 1), Some(1));
+        assert_eq!(pow_mod(2, 3, 0), None);
+    }
+
+    #[test]
+    pub fn test_crt_against_brute_force() {
+        let mut rng = Rng::new(3);
+        for _ in 0..1000 {
+            let congruences = (0..1 + rng.below(3))
+                .map(|_| {
+                    let m = 1 + rng.below(12) as i64;
+                    (rng.below(30) as i64 - 15, m)
+                })
+                .collect::<Vec<_>>();
+            let modulus = lcm_all(&congruences.iter().map(|(_, m)| *m as u64).collect::<Vec<_>>()).unwrap() as i64;
+            let expected = (0..modulus).find(|x| congruences.iter().all(|(r, m)| (x - r).rem_euclid(*m) == 0));
+            assert_eq!(crt(&congruences), expected.map(|x| (x, modulus)));
+        }
+    }
+
+    #[test]
+    pub fn test_checked() {
+        assert_eq!(checked_sum(&[1, 2, 3]), Some(6));
+        assert_eq!(checked_sum(&[i128::MAX, 1]), None);
+        assert_eq!(checked_product(&[i128::MAX / 2, 3]), None);
+    }
+}
@@ -0,0 +1,371 @@
+//! Small parser combinators for the line formats that keep coming back in the puzzles.
+//!
+//! A parser is any function from the remaining input to the parsed value and the input left after
+//! it. Parsers never copy the input, and backtracking is just retrying on the same slice. Use
+//! [`parse`] to run a parser over a complete string and get an error with a position on failure.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A failed parse, with the byte offset in the parsed string where it happened, and the line
+/// number when parsing multi-line input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub position: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Sets the (1-based) line number of the line that failed to parse.
+    pub fn at_line(self, line: usize) -> ParseError {
+        ParseError { line: Some(line), ..self }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        if self.found.is_empty() {
+            write!(f, "expected {} at position {}, found end of input", self.expected, self.position)
+        } else {
+            write!(f, "expected {} at position {}, found \"{}\"", self.expected, self.position, self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A failure while parsing, pointing at the remaining input where it happened.
+#[derive(Debug, Clone)]
+pub struct Failure<'a> {
+    at: &'a str,
+    expected: String,
+}
+
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+/// Anything that can parse a T off the front of the input.
+pub trait Parser<'a, T>: Fn(&'a str) -> PResult<'a, T> {}
+
+impl<'a, T, F> Parser<'a, T> for F where F: Fn(&'a str) -> PResult<'a, T> {}
+
+fn fail<'a, T>(at: &'a str, expected: impl Into<String>) -> PResult<'a, T> {
+    Err(Failure { at, expected: expected.into() })
+}
+
+/// Runs the parser over the whole input, failing if anything is left over.
+pub fn parse<'a, T>(parser: impl Parser<'a, T>, input: &'a str) -> Result<T, ParseError> {
+    let to_error = |failure: Failure<'a>| ParseError {
+        line: None,
+        position: input.len() - failure.at.len(),
+        expected: failure.expected,
+        found: failure.at.chars().take(10).collect(),
+    };
+    match parser(input) {
+        Ok((value, "")) => Ok(value),
+        Ok((_, rest)) => Err(to_error(Failure { at: rest, expected: "end of input".to_string() })),
+        Err(failure) => Err(to_error(failure)),
+    }
+}
+
+/// Matches a literal string.
+pub fn tag<'a>(literal: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(literal) {
+        Some(rest) => Ok((&input[..literal.len()], rest)),
+        None => fail(input, format!("\"{}\"", literal)),
+    }
+}
+
+/// Takes the longest non-empty prefix whose chars all satisfy the predicate.
+pub fn take_while1<'a>(predicate: impl Fn(char) -> bool, expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        let end = input.find(|ch: char| !predicate(ch)).unwrap_or(input.len());
+        if end == 0 { fail(input, expected) } else { Ok((&input[..end], &input[end..])) }
+    }
+}
+
+/// An identifier-like run of letters and digits.
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(|ch| ch.is_ascii_alphanumeric(), "a word")
+}
+
+/// Skips any number of spaces.
+pub fn space0<'a>() -> impl Parser<'a, ()> {
+    move |input: &'a str| Ok(((), input.trim_start_matches(' ')))
+}
+
+/// Skips at least one space.
+pub fn space1<'a>() -> impl Parser<'a, ()> {
+    map(take_while1(|ch| ch == ' ', "a space"), |_| ())
+}
+
+/// An unsigned decimal integer.
+pub fn unsigned<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        let (digits, rest) = take_while1(|ch| ch.is_ascii_digit(), "an unsigned integer")(input)?;
+        match digits.parse::<T>() {
+            Ok(n) => Ok((n, rest)),
+            Err(_) => fail(input, "an integer in range"),
+        }
+    }
+}
+
+/// A decimal integer with an optional sign.
+pub fn signed<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        let unsigned_part = input.strip_prefix(['-', '+']).unwrap_or(input);
+        let (digits, rest) = take_while1(|ch| ch.is_ascii_digit(), "a signed integer")(unsigned_part)?;
+        let len = input.len() - unsigned_part.len() + digits.len();
+        match input[..len].parse::<T>() {
+            Ok(n) => Ok((n, rest)),
+            Err(_) => fail(input, "an integer in range"),
+        }
+    }
+}
+
+/// Transforms the parsed value.
+pub fn map<'a, A, B>(parser: impl Parser<'a, A>, f: impl Fn(A) -> B) -> impl Parser<'a, B> {
+    move |input: &'a str| parser(input).map(|(a, rest)| (f(a), rest))
+}
+
+/// Parses `a` then `b`, keeping both.
+pub fn pair<'a, A, B>(a: impl Parser<'a, A>, b: impl Parser<'a, B>) -> impl Parser<'a, (A, B)> {
+    move |input: &'a str| {
+        let (va, rest) = a(input)?;
+        let (vb, rest) = b(rest)?;
+        Ok(((va, vb), rest))
+    }
+}
+
+/// Parses `a` then `b`, keeping only `b`.
+pub fn preceded<'a, A, B>(a: impl Parser<'a, A>, b: impl Parser<'a, B>) -> impl Parser<'a, B> {
+    map(pair(a, b), |(_, vb)| vb)
+}
+
+/// Parses `a` then `b`, keeping only `a`.
+pub fn terminated<'a, A, B>(a: impl Parser<'a, A>, b: impl Parser<'a, B>) -> impl Parser<'a, A> {
+    map(pair(a, b), |(va, _)| va)
+}
+
+/// Parses `open`, `inner` and `close`, keeping only `inner`.
+pub fn delimited<'a, A, B, C>(open: impl Parser<'a, A>, inner: impl Parser<'a, B>, close: impl Parser<'a, C>) -> impl Parser<'a, B> {
+    preceded(open, terminated(inner, close))
+}
+
+/// Tries `a`, falling back to `b` if it fails.
+pub fn alt<'a, T>(a: impl Parser<'a, T>, b: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |input: &'a str| match a(input) {
+        Ok(result) => Ok(result),
+        Err(fa) => b(input).map_err(|fb| {
+            // Report whichever alternative got furthest.
+            if fa.at.len() < fb.at.len() { fa } else { fb }
+        }),
+    }
+}
+
+/// Optionally parses something, succeeding without consuming input if it isn't there.
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input: &'a str| match parser(input) {
+        Ok((v, rest)) => Ok((Some(v), rest)),
+        Err(_) => Ok((None, input)),
+    }
+}
+
+/// One or more items separated by `sep`. Once a separator has matched, an item must follow, so
+/// that an error inside the list is reported where it happened.
+pub fn separated_list1<'a, T, S>(item: impl Parser<'a, T>, sep: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str| {
+        let (first, mut rest) = item(input)?;
+        let mut items = vec![first];
+        while let Ok((_, after_sep)) = sep(rest) {
+            let (next, after) = item(after_sep)?;
+            items.push(next);
+            rest = after;
+        }
+        Ok((items, rest))
+    }
+}
+
+/// One or more items, stopping at the first one that doesn't parse.
+pub fn many1<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str| {
+        let (first, mut rest) = item(input)?;
+        let mut items = vec![first];
+        while let Ok((next, after)) = item(rest) {
+            items.push(next);
+            rest = after;
+        }
+        Ok((items, rest))
+    }
+}
+
+/// One or more items separated by whitespace, allowing leading whitespace as in `"  1 21  3"`.
+/// Whitespace before something that isn't an item is left unparsed.
+pub fn ws_list<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    many1(preceded(space0(), item))
+}
+
+/// One or more items separated by commas, with optional spaces after each comma.
+pub fn comma_list<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    separated_list1(item, pair(tag(","), space0()))
+}
+
+/// A key and a value separated by a literal, such as `rn=1` or `AAA = (BBB, CCC)`.
+pub fn key_value<'a, K, V>(key: impl Parser<'a, K>, sep: &'static str, value: impl Parser<'a, V>) -> impl Parser<'a, (K, V)> {
+    pair(terminated(key, tag(sep)), value)
+}
+
+/// A labelled section such as `Card 1: ...`, returning the label and the body. Spaces after the
+/// colon are skipped.
+pub fn labelled<'a, L, B>(label: impl Parser<'a, L>, body: impl Parser<'a, B>) -> impl Parser<'a, (L, B)> {
+    pair(terminated(label, pair(tag(":"), space0())), body)
+}
+
+/// Types that can be parsed from a single line of puzzle input, usually by deriving it with
+/// `#[derive(FromInput)]` and an `#[input("...")]` format.
+pub trait FromInput: Sized {
+    fn from_line(line: &str) -> Result<Self, ParseError>;
+}
+
+/// Scans a line piece by piece against a format. This is what `#[derive(FromInput)]` generates
+/// code for, but it works by hand too.
+pub struct Cursor<'a> {
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(line: &'a str) -> Cursor<'a> {
+        Cursor { line, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    fn skip_spaces(&mut self) {
+        self.pos = self.line.len() - self.rest().trim_start_matches(' ').len();
+    }
+
+    fn error(&self, position: usize, expected: String) -> ParseError {
+        ParseError { line: None, position, expected, found: self.line[position..].chars().take(10).collect() }
+    }
+
+    /// Matches a literal. Leading spaces are skipped, and whitespace within the literal matches any
+    /// run of spaces.
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        for word in literal.split_whitespace() {
+            self.skip_spaces();
+            if !self.rest().starts_with(word) {
+                return Err(self.error(self.pos, format!("\"{}\"", word)));
+            }
+            self.pos += word.len();
+        }
+        Ok(())
+    }
+
+    /// Takes the text of a field, up to the literal that follows it (or the next space if that
+    /// literal is only whitespace, or the end of the line if there is none). Returns the trimmed
+    /// text together with its position.
+    pub fn field(&mut self, next: Option<&str>) -> (usize, &'a str) {
+        self.skip_spaces();
+        let rest = self.rest();
+        let end = match next.map(|n| n.split_whitespace().next()) {
+            Some(Some(word)) => rest.find(word).unwrap_or(rest.len()),
+            Some(None) => rest.find(' ').unwrap_or(rest.len()),
+            None => rest.len(),
+        };
+        let start = self.pos;
+        self.pos += end;
+        (start, rest[..end].trim_end())
+    }
+
+    /// Parses the text of a field with `FromStr`.
+    pub fn value<T: FromStr>(&self, field: (usize, &str), name: &str) -> Result<T, ParseError> {
+        let (position, text) = field;
+        text.parse::<T>().map_err(|_| self.error(position, format!("a value for `{}`", name)))
+    }
+
+    /// Parses the text of a field as a list, separated by `sep` or by whitespace if there is none.
+    pub fn list<C>(&self, field: (usize, &str), sep: Option<char>, name: &str) -> Result<C, ParseError>
+    where
+        C: IntoIterator + FromIterator<<C as IntoIterator>::Item>,
+        <C as IntoIterator>::Item: FromStr,
+    {
+        let (position, text) = field;
+        let pieces: Vec<&str> = match sep {
+            Some(sep) => text.split(sep).collect(),
+            None => text.split(' ').filter(|piece| !piece.is_empty()).collect(),
+        };
+        pieces
+            .into_iter()
+            .map(|piece| {
+                // Pieces are slices of the text, so their offset gives the position in the line.
+                let offset = piece.as_ptr() as usize - text.as_ptr() as usize;
+                let trimmed = piece.trim();
+                let offset = offset + piece.len() - piece.trim_start().len();
+                trimmed.parse().map_err(|_| self.error(position + offset, format!("an item of `{}`", name)))
+            })
+            .collect()
+    }
+
+    /// Checks that nothing but spaces is left.
+    pub fn finish(&mut self) -> Result<(), ParseError> {
+        self.skip_spaces();
+        if self.pos == self.line.len() {
+            Ok(())
+        } else {
+            Err(self.error(self.pos, "end of line".to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse::*;
+
+    #[test]
+    pub fn test_integers() {
+        assert_eq!(parse(unsigned::<u32>(), "123"), Ok(123));
+        assert_eq!(parse(signed::<i64>(), "-42"), Ok(-42));
+        assert_eq!(parse(signed::<i64>(), "+7"), Ok(7));
+        assert!(parse(unsigned::<u8>(), "300").is_err());
+        assert!(parse(unsigned::<u32>(), "-1").is_err());
+    }
+
+    #[test]
+    pub fn test_lists() {
+        assert_eq!(parse(ws_list(unsigned::<u32>()), "  1 21  3"), Ok(vec![1, 21, 3]));
+        assert_eq!(parse(comma_list(signed::<i32>()), "1,-2, 3"), Ok(vec![1, -2, 3]));
+    }
+
+    #[test]
+    pub fn test_labelled() {
+        let card = labelled(
+            preceded(pair(tag("Card"), space1()), unsigned::<u32>()),
+            pair(terminated(ws_list(unsigned::<u32>()), pair(space0(), tag("|"))), ws_list(unsigned::<u32>())),
+        );
+        assert_eq!(parse(card, "Card  3:  1 21 | 69 82"), Ok((3, (vec![1, 21], vec![69, 82]))));
+    }
+
+    #[test]
+    pub fn test_key_value() {
+        let node = key_value(word(), " = ", delimited(tag("("), pair(terminated(word(), tag(", ")), word()), tag(")")));
+        assert_eq!(parse(node, "AAA = (BBB, CCC)"), Ok(("AAA", ("BBB", "CCC"))));
+    }
+
+    #[test]
+    pub fn test_error_position() {
+        let err = parse(key_value(word(), "=", unsigned::<u8>()), "rn=x").unwrap_err();
+        assert_eq!(err.position, 3);
+        assert_eq!(err.to_string(), "expected an unsigned integer at position 3, found \"x\"");
+
+        let err = parse(word(), "abc def").unwrap_err();
+        assert_eq!(err.position, 3);
+        assert_eq!(err.expected, "end of input");
+    }
+}
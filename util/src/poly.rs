@@ -0,0 +1,125 @@
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedMul, Zero};
+
+/// The polynomial of lowest degree through a sequence of values, in Newton forward-difference
+/// form: f(x) = sum over k of diffs[k] * C(x, k), where diffs[k] is the k-th difference at index 0.
+/// Every value and difference is an integer, so evaluating at any integer index stays exact.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial {
+    diffs: Vec<i128>,
+}
+
+impl Polynomial {
+    /// Builds the polynomial with f(i) = values[i]. Returns None for an empty sequence or if a
+    /// difference overflows.
+    pub fn from_values(values: &[i64]) -> Option<Polynomial> {
+        let mut row = values.iter().map(|v| *v as i128).collect::<Vec<_>>();
+        let mut diffs = Vec::with_capacity(row.len());
+        while let Some(first) = row.first() {
+            diffs.push(*first);
+            row = row.windows(2).map(|w| w[1].checked_sub(w[0])).collect::<Option<Vec<_>>>()?;
+        }
+        if diffs.is_empty() {
+            return None;
+        }
+        // Trailing zero differences don't contribute, dropping them gives the true degree.
+        while diffs.len() > 1 && diffs.last() == Some(&0) {
+            diffs.pop();
+        }
+        Some(Polynomial { diffs })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.diffs.len() - 1
+    }
+
+    /// The forward differences at index 0, lowest order first.
+    pub fn differences(&self) -> &[i128] {
+        &self.diffs
+    }
+
+    /// Evaluates the polynomial at any index, also before the first value or far beyond the last.
+    /// Returns None on overflow.
+    pub fn eval(&self, x: i64) -> Option<i128> {
+        let x = x as i128;
+        let mut binomial: i128 = 1; // C(x, k), generalized to negative x
+        let mut sum: i128 = 0;
+        for (k, &diff) in self.diffs.iter().enumerate() {
+            if k > 0 {
+                // C(x, k) = C(x, k - 1) * (x - k + 1) / k, and the division is always exact.
+                binomial = binomial.checked_mul(x - k as i128 + 1)? / k as i128;
+            }
+            sum = sum.checked_add(diff.checked_mul(binomial)?)?;
+        }
+        Some(sum)
+    }
+
+    /// The coefficients in the usual power basis, lowest power first. Returns None on overflow.
+    pub fn coefficients(&self) -> Option<Vec<Ratio<i128>>> {
+        let mut coefficients = vec![Ratio::zero(); self.diffs.len()];
+        // x(x - 1)...(x - k + 1) with integer coefficients, lowest power first, and k!.
+        let mut falling: Vec<i128> = vec![1];
+        let mut factorial: i128 = 1;
+        for (k, &diff) in self.diffs.iter().enumerate() {
+            if k > 0 {
+                let shift = k as i128 - 1;
+                let mut next = vec![0i128; falling.len() + 1];
+                for (i, &c) in falling.iter().enumerate() {
+                    next[i + 1] = next[i + 1].checked_add(c)?;
+                    next[i] = next[i].checked_sub(c.checked_mul(shift)?)?;
+                }
+                falling = next;
+                factorial = factorial.checked_mul(k as i128)?;
+            }
+            for (i, &c) in falling.iter().enumerate() {
+                let term = Ratio::new(c.checked_mul(diff)?, 1).checked_mul(&Ratio::new(1, factorial))?;
+                coefficients[i] = coefficients[i].checked_add(&term)?;
+            }
+        }
+        Some(coefficients)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::poly::Polynomial;
+    use num_rational::Ratio;
+
+    #[test]
+    pub fn test_extrapolate() {
+        let p = Polynomial::from_values(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(p.degree(), 3);
+        assert_eq!(p.eval(6), Some(68));
+        assert_eq!(p.eval(-1), Some(5));
+        assert_eq!((0..6).map(|x| p.eval(x).unwrap()).collect::<Vec<_>>(), vec![10, 13, 16, 21, 30, 45]);
+
+        let constant = Polynomial::from_values(&[7, 7, 7]).unwrap();
+        assert_eq!(constant.degree(), 0);
+        assert_eq!(constant.eval(-1_000_000), Some(7));
+        assert_eq!(Polynomial::from_values(&[]), None);
+    }
+
+    #[test]
+    pub fn test_far_indices() {
+        // f(x) = 3x^2 - 2x + 5
+        let f = |x: i128| 3 * x * x - 2 * x + 5;
+        let p = Polynomial::from_values(&(0..4).map(|x| f(x) as i64).collect::<Vec<_>>()).unwrap();
+        for x in [-26501365i64, -1, 100, 26501365, 3_000_000_000] {
+            assert_eq!(p.eval(x), Some(f(x as i128)));
+        }
+        assert_eq!(p.coefficients(), Some(vec![Ratio::from(5), Ratio::from(-2), Ratio::from(3)]));
+    }
+
+    #[test]
+    pub fn test_rational_coefficients() {
+        // Triangular numbers: x(x + 1) / 2
+        let p = Polynomial::from_values(&[0, 1, 3, 6, 10]).unwrap();
+        assert_eq!(p.coefficients(), Some(vec![Ratio::from(0), Ratio::new(1, 2), Ratio::new(1, 2)]));
+    }
+
+    #[test]
+    pub fn test_overflow() {
+        let cubic = Polynomial::from_values(&[0, 1, 8, 27]).unwrap();
+        assert_eq!(cubic.eval(i64::MAX), None);
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Integer types that can be scanned directly from ASCII digits.
+pub trait Integer: Copy {
+    const SIGNED: bool;
+
+    fn zero() -> Self;
+    /// Returns `self * 10 + digit`, or `self * 10 - digit` for a negative number being built up, or
+    /// None if that overflows.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+}
+
+/// A number in the input that doesn't fit in the type it is scanned as.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// The number as written, including a minus sign.
+    pub text: String,
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} doesn't fit in {}", self.text, self.type_name)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+macro_rules! integer {
+    ($signed:expr => $($t:ty),*) => {
+        $(impl Integer for $t {
+            const SIGNED: bool = $signed;
+
+            fn zero() -> Self {
+                0
+            }
+
+            fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                let shifted = self.checked_mul(10)?;
+                if negative {
+                    shifted.checked_sub(digit as $t)
+                } else {
+                    shifted.checked_add(digit as $t)
+                }
+            }
+        })*
+    };
+}
+
+integer!(false => u8, u16, u32, u64, u128, usize);
+integer!(true => i8, i16, i32, i64, i128, isize);
+
+/// Iterator over the integers in a byte slice, skipping everything that isn't a digit. For signed
+/// types a `-` directly in front of the digits makes the number negative. A number that overflows
+/// the type is reported as an error, and scanning goes on after it.
+pub struct Numbers<'a, T> {
+    bytes: &'a [u8],
+    pos: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: Integer> Iterator for Numbers<'a, T> {
+    type Item = Result<T, Overflow>;
+
+    fn next(&mut self) -> Option<Result<T, Overflow>> {
+        let bytes = self.bytes;
+        while self.pos < bytes.len() && !bytes[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        if self.pos == bytes.len() {
+            return None;
+        }
+        let negative = T::SIGNED && self.pos > 0 && bytes[self.pos - 1] == b'-';
+        let start = if negative { self.pos - 1 } else { self.pos };
+        let mut n = Some(T::zero());
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_digit() {
+            n = n.and_then(|n| n.push_digit(bytes[self.pos] - b'0', negative));
+            self.pos += 1;
+        }
+        Some(n.ok_or_else(|| Overflow {
+            text: String::from_utf8_lossy(&bytes[start..self.pos]).into_owned(),
+            type_name: std::any::type_name::<T>(),
+        }))
+    }
+}
+
+/// Scans all integers in the given bytes, without allocating.
+pub fn numbers<T: Integer>(bytes: &[u8]) -> Numbers<'_, T> {
+    Numbers { bytes, pos: 0, _marker: PhantomData }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scan::{numbers, Overflow};
+
+    #[test]
+    pub fn test_numbers() {
+        let all = |bytes: &[u8]| numbers::<i64>(bytes).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(numbers::<u32>(b"Card  1: 41 48 | 83").collect::<Result<Vec<_>, _>>(), Ok(vec![1, 41, 48, 83]));
+        assert_eq!(all(b"-3 4 -12,x-0 7-8"), vec![-3, 4, -12, 0, 7, -8]);
+        assert_eq!(numbers::<u64>(b"-3 4").collect::<Result<Vec<_>, _>>(), Ok(vec![3, 4]));
+        assert_eq!(numbers::<i8>(b"-128 127").collect::<Result<Vec<_>, _>>(), Ok(vec![-128, 127]));
+        assert_eq!(numbers::<u8>(b"no numbers").count(), 0);
+    }
+
+    #[test]
+    pub fn test_overflow() {
+        let scanned = numbers::<u8>(b"255 256 -1 99999 7").collect::<Vec<_>>();
+        assert_eq!(scanned[0], Ok(255));
+        assert_eq!(scanned[1].as_ref().unwrap_err().to_string(), "256 doesn't fit in u8");
+        assert_eq!(scanned[2..], [Ok(1), Err(Overflow { text: "99999".to_string(), type_name: "u8" }), Ok(7)]);
+        assert_eq!(numbers::<i8>(b"-129").next().unwrap().unwrap_err().text, "-129");
+        assert!(numbers::<u64>(b"18446744073709551616").next().unwrap().is_err());
+    }
+}
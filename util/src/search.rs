@@ -0,0 +1,261 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The outcome of a search: the distance to every settled state, how each state was reached, and
+/// the goal state if one was found.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    pub distances: HashMap<S, C>,
+    pub predecessors: HashMap<S, S>,
+    pub goal: Option<S>,
+}
+
+impl<S, C> SearchResult<S, C>
+where
+    S: Eq + Hash + Clone,
+    C: Copy,
+{
+    /// Reconstructs the path from a start state to the given state, both ends included.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+        let mut path = vec![state.clone()];
+        let mut current = state;
+        while let Some(prev) = self.predecessors.get(current) {
+            path.push(prev.clone());
+            current = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn goal_distance(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|g| self.distances.get(g).copied())
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|g| self.path_to(g))
+    }
+}
+
+/// Breadth-first search from one or more start states, where every step has length 1. Stops at
+/// the first state satisfying `is_goal`; pass `|_| false` to explore everything reachable.
+pub fn bfs<S, I, FN, FG>(starts: impl IntoIterator<Item = S>, successors: FN, is_goal: FG) -> SearchResult<S, usize>
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = S>,
+    FN: Fn(&S) -> I,
+    FG: Fn(&S) -> bool,
+{
+    let mut distances: HashMap<S, usize> = HashMap::new();
+    let mut predecessors: HashMap<S, S> = HashMap::new();
+    let mut queue: VecDeque<S> = VecDeque::new();
+    for start in starts {
+        if !distances.contains_key(&start) {
+            distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            return SearchResult { distances, predecessors, goal: Some(state) };
+        }
+        let dist = distances[&state];
+        for next in successors(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), dist + 1);
+                predecessors.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    SearchResult { distances, predecessors, goal: None }
+}
+
+/// Dijkstra's algorithm from one or more start states. Costs must be non-negative, and
+/// `C::default()` is taken as zero.
+pub fn dijkstra<S, C, I, FN, FG>(starts: impl IntoIterator<Item = S>, successors: FN, is_goal: FG) -> SearchResult<S, C>
+where
+    S: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    FN: Fn(&S) -> I,
+    FG: Fn(&S) -> bool,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// Heap entry ordered so that the lowest priority is popped first.
+struct Entry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// A* search from one or more start states. The heuristic must never overestimate the remaining
+/// cost to a goal, and should be consistent for the distances of non-goal states to be exact.
+pub fn astar<S, C, I, FN, FH, FG>(starts: impl IntoIterator<Item = S>, successors: FN, heuristic: FH, is_goal: FG) -> SearchResult<S, C>
+where
+    S: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    FN: Fn(&S) -> I,
+    FH: Fn(&S) -> C,
+    FG: Fn(&S) -> bool,
+{
+    let mut best: HashMap<S, C> = HashMap::new();
+    let mut distances: HashMap<S, C> = HashMap::new();
+    let mut predecessors: HashMap<S, S> = HashMap::new();
+    let mut heap: BinaryHeap<Entry<S, C>> = BinaryHeap::new();
+    for start in starts {
+        best.insert(start.clone(), C::default());
+        heap.push(Entry { priority: heuristic(&start), cost: C::default(), state: start });
+    }
+
+    while let Some(Entry { cost, state, .. }) = heap.pop() {
+        if distances.contains_key(&state) || best.get(&state).is_some_and(|b| *b < cost) {
+            continue; // stale entry
+        }
+        distances.insert(state.clone(), cost);
+        if is_goal(&state) {
+            return SearchResult { distances, predecessors, goal: Some(state) };
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            if distances.contains_key(&next) || best.get(&next).is_some_and(|b| *b <= next_cost) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            predecessors.insert(next.clone(), state.clone());
+            heap.push(Entry { priority: next_cost + heuristic(&next), cost: next_cost, state: next });
+        }
+    }
+
+    SearchResult { distances, predecessors, goal: None }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rng::Rng;
+    use crate::search::{astar, bfs, dijkstra};
+
+    fn random_graph(rng: &mut Rng) -> Vec<Vec<(usize, u32)>> {
+        let n = 1 + rng.below(7) as usize;
+        (0..n)
+            .map(|_| (0..rng.below(4)).map(|_| (rng.below(n as u64) as usize, rng.below(10) as u32)).collect())
+            .collect()
+    }
+
+    /// Tries every simple path, keeping the cheapest cost to each node.
+    fn brute_force(graph: &[Vec<(usize, u32)>], start: usize, unit: bool) -> Vec<Option<u32>> {
+        fn walk(graph: &[Vec<(usize, u32)>], node: usize, cost: u32, unit: bool, visited: &mut Vec<bool>, best: &mut Vec<Option<u32>>) {
+            if best[node].is_none_or(|b| cost < b) {
+                best[node] = Some(cost);
+            }
+            visited[node] = true;
+            for (next, c) in graph[node].iter() {
+                if !visited[*next] {
+                    walk(graph, *next, cost + if unit { 1 } else { *c }, unit, visited, best);
+                }
+            }
+            visited[node] = false;
+        }
+        let mut best = vec![None; graph.len()];
+        walk(graph, start, 0, unit, &mut vec![false; graph.len()], &mut best);
+        best
+    }
+
+    fn path_cost(graph: &[Vec<(usize, u32)>], path: &[usize]) -> u32 {
+        path.windows(2)
+            .map(|w| graph[w[0]].iter().filter(|(n, _)| *n == w[1]).map(|(_, c)| *c).min().unwrap())
+            .sum()
+    }
+
+    #[test]
+    pub fn test_bfs_against_brute_force() {
+        let mut rng = Rng::new(1);
+        for _ in 0..200 {
+            let graph = random_graph(&mut rng);
+            let expected = brute_force(&graph, 0, true);
+            let result = bfs([0], |n| graph[*n].iter().map(|(m, _)| *m).collect::<Vec<_>>(), |_| false);
+            for (node, exp) in expected.iter().enumerate() {
+                assert_eq!(result.distances.get(&node).map(|d| *d as u32), *exp);
+                if let Some(path) = result.path_to(&node) {
+                    assert_eq!(path.len() - 1, result.distances[&node]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_dijkstra_against_brute_force() {
+        let mut rng = Rng::new(2);
+        for _ in 0..200 {
+            let graph = random_graph(&mut rng);
+            let expected = brute_force(&graph, 0, false);
+            let result = dijkstra([0], |n| graph[*n].clone(), |_| false);
+            for (node, exp) in expected.iter().enumerate() {
+                assert_eq!(result.distances.get(&node).copied(), *exp);
+                if let Some(path) = result.path_to(&node) {
+                    assert_eq!(path[0], 0);
+                    assert_eq!(path_cost(&graph, &path), result.distances[&node]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_astar_against_brute_force() {
+        let mut rng = Rng::new(3);
+        for _ in 0..200 {
+            let graph = random_graph(&mut rng);
+            let goal = rng.below(graph.len() as u64) as usize;
+            // The exact remaining distance is the best possible consistent heuristic.
+            let reversed: Vec<Vec<(usize, u32)>> = (0..graph.len())
+                .map(|m| (0..graph.len()).flat_map(|n| graph[n].iter().filter(move |(t, _)| *t == m).map(move |(_, c)| (n, *c))).collect())
+                .collect();
+            let to_goal = brute_force(&reversed, goal, false);
+            let expected = brute_force(&graph, 0, false)[goal];
+
+            let result = astar([0], |n| graph[*n].clone(), |n| to_goal[*n].unwrap_or(0), |n| *n == goal);
+            assert_eq!(result.goal_distance(), expected);
+            if let Some(path) = result.goal_path() {
+                assert_eq!(Some(path_cost(&graph, &path)), expected);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_multi_source() {
+        // A line 0 - 1 - 2 - 3 - 4 searched from both ends.
+        let result = bfs([0, 4], |n: &i32| [n - 1, n + 1].into_iter().filter(|m| (0..=4).contains(m)), |_| false);
+        assert_eq!(result.distances[&2], 2);
+        assert_eq!(result.distances[&3], 1);
+        assert_eq!(result.path_to(&3), Some(vec![4, 3]));
+    }
+}
@@ -0,0 +1,195 @@
+//! Terminal animation of grid simulations: each frame is a grid of coloured chars that is redrawn
+//! in place, either after a delay or when Enter is pressed.
+
+use std::io::{self, BufRead, Stdout, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::grid::Pos;
+
+pub mod svg;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+impl Color {
+    fn ansi(&self) -> &'static str {
+        match self {
+            Color::Default => "\x1b[0m",
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::Gray => "\x1b[90m",
+        }
+    }
+}
+
+/// One picture of the simulation, with an optional caption drawn below the grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    cells: Vec<Vec<(char, Color)>>,
+    caption: String,
+}
+
+impl Frame {
+    /// Builds a frame from lines of text, colouring each char.
+    pub fn from_text(text: &str, color_of: impl Fn(char) -> Color) -> Frame {
+        let cells = text.lines().map(|line| line.chars().map(|ch| (ch, color_of(ch))).collect()).collect();
+        Frame { cells, caption: String::new() }
+    }
+
+    /// Replaces the cell at the position, ignoring positions outside the frame.
+    pub fn set(&mut self, pos: Pos, ch: char, color: Color) {
+        if let Some(cell) = self.cells.get_mut(pos.1).and_then(|row| row.get_mut(pos.0)) {
+            *cell = (ch, color);
+        }
+    }
+
+    pub fn with_caption(self, caption: impl Into<String>) -> Frame {
+        Frame { caption: caption.into(), ..self }
+    }
+
+    /// The frame as text with ANSI colour codes, only switching colour where it changes.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for row in self.cells.iter() {
+            let mut current = Color::Default;
+            for (ch, color) in row.iter() {
+                if *color != current {
+                    out.push_str(color.ansi());
+                    current = *color;
+                }
+                out.push(*ch);
+            }
+            if current != Color::Default {
+                out.push_str(Color::Default.ansi());
+            }
+            out.push('\n');
+        }
+        if !self.caption.is_empty() {
+            out.push_str(&self.caption);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// How the animation moves on to the next frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pace {
+    /// Wait the given time between frames.
+    Delay(Duration),
+    /// Wait for Enter, or "q" and Enter to stop.
+    Step,
+}
+
+impl Pace {
+    /// Reads the animation flags from the command line: `--animate` turns animation on with a
+    /// 100ms delay, `--delay <ms>` sets the delay and `--step` steps with the keyboard. Returns
+    /// None if animation isn't wanted.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Pace>, String> {
+        let mut pace = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--animate" => pace = pace.or(Some(Pace::Delay(Duration::from_millis(100)))),
+                "--step" => pace = Some(Pace::Step),
+                "--delay" => {
+                    let ms = args.next().ok_or("--delay needs a number of milliseconds")?;
+                    let ms = ms.parse::<u64>().map_err(|_| format!("invalid delay: {}", ms))?;
+                    pace = Some(Pace::Delay(Duration::from_millis(ms)));
+                }
+                other => return Err(format!("unknown argument: {}", other)),
+            }
+        }
+        Ok(pace)
+    }
+}
+
+/// Draws frames over each other on a terminal (or any writer, for testing).
+pub struct Animator<W: Write, R: BufRead> {
+    out: W,
+    input: R,
+    pace: Pace,
+}
+
+impl Animator<Stdout, io::StdinLock<'static>> {
+    pub fn terminal(pace: Pace) -> Self {
+        Animator::new(io::stdout(), io::stdin().lock(), pace)
+    }
+}
+
+impl<W: Write, R: BufRead> Animator<W, R> {
+    pub fn new(out: W, input: R, pace: Pace) -> Self {
+        Animator { out, input, pace }
+    }
+
+    /// Clears the screen, draws the frame and waits according to the pace. Returns false when the
+    /// user asked to stop.
+    pub fn show(&mut self, frame: &Frame) -> io::Result<bool> {
+        write!(self.out, "\x1b[2J\x1b[H{}", frame.render())?;
+        match self.pace {
+            Pace::Delay(delay) => {
+                self.out.flush()?;
+                thread::sleep(delay);
+                Ok(true)
+            }
+            Pace::Step => {
+                write!(self.out, "[Enter] next frame, [q] quit ")?;
+                self.out.flush()?;
+                let mut line = String::new();
+                let read = self.input.read_line(&mut line)?;
+                Ok(read > 0 && line.trim() != "q")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use crate::viz::{Animator, Color, Frame, Pace};
+
+    #[test]
+    pub fn test_render() {
+        let mut frame = Frame::from_text("#.\n.O\n", |ch| if ch == '#' { Color::Gray } else { Color::Default });
+        frame.set((1, 1), 'O', Color::Yellow);
+        frame.set((5, 5), 'X', Color::Red);
+        let frame = frame.with_caption("step 1");
+        assert_eq!(frame.render(), "\x1b[90m#\x1b[0m.\n.\x1b[33mO\x1b[0m\nstep 1\n");
+    }
+
+    #[test]
+    pub fn test_step() {
+        let frame = Frame::from_text("ab", |_| Color::Default);
+        let mut out = Vec::new();
+        let mut animator = Animator::new(&mut out, Cursor::new("\nq\n"), Pace::Step);
+        assert!(animator.show(&frame).unwrap());
+        assert!(!animator.show(&frame).unwrap());
+        assert!(String::from_utf8(out).unwrap().starts_with("\x1b[2J\x1b[Hab\n[Enter]"));
+    }
+
+    #[test]
+    pub fn test_from_args() {
+        let args = |s: &str| s.split_whitespace().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(Pace::from_args(args("")), Ok(None));
+        assert_eq!(Pace::from_args(args("--animate")), Ok(Some(Pace::Delay(Duration::from_millis(100)))));
+        assert_eq!(Pace::from_args(args("--delay 20")), Ok(Some(Pace::Delay(Duration::from_millis(20)))));
+        assert_eq!(Pace::from_args(args("--animate --step")), Ok(Some(Pace::Step)));
+        assert!(Pace::from_args(args("--delay x")).is_err());
+    }
+}
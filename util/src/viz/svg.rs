@@ -0,0 +1,210 @@
+//! Standalone SVG pictures of puzzle states: a grid of styled cells with polylines and point
+//! markers drawn on top. Positions are grid cells; lines and markers go through cell centres.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::grid::Pos;
+
+/// How to draw one cell: a fill colour and optionally a char written in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellStyle {
+    pub fill: String,
+    pub label: Option<char>,
+}
+
+impl CellStyle {
+    pub fn fill(fill: &str) -> CellStyle {
+        CellStyle { fill: fill.to_string(), label: None }
+    }
+
+    pub fn with_label(self, label: char) -> CellStyle {
+        CellStyle { label: Some(label), ..self }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Polyline {
+    points: Vec<Pos>,
+    stroke: String,
+    closed: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Marker {
+    pos: Pos,
+    fill: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Svg {
+    width: usize,
+    height: usize,
+    cell_size: usize,
+    cells: Vec<(Pos, CellStyle)>,
+    polylines: Vec<Polyline>,
+    markers: Vec<Marker>,
+}
+
+impl Svg {
+    /// An empty picture of a grid of the given size in cells.
+    pub fn new(width: usize, height: usize) -> Svg {
+        Svg { width, height, cell_size: 10, cells: vec![], polylines: vec![], markers: vec![] }
+    }
+
+    /// Sets the size of a cell in pixels, 10 by default.
+    pub fn cell_size(&mut self, cell_size: usize) -> &mut Svg {
+        self.cell_size = cell_size;
+        self
+    }
+
+    pub fn cell(&mut self, pos: Pos, style: CellStyle) -> &mut Svg {
+        self.cells.push((pos, style));
+        self
+    }
+
+    /// Styles every cell of the grid, skipping the cells the function returns None for.
+    pub fn cells(&mut self, style_of: impl Fn(Pos) -> Option<CellStyle>) -> &mut Svg {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if let Some(style) = style_of((x, y)) {
+                    self.cells.push(((x, y), style));
+                }
+            }
+        }
+        self
+    }
+
+    /// A line through the centres of the cells, back to the first one if `closed`.
+    pub fn polyline(&mut self, points: &[Pos], stroke: &str, closed: bool) -> &mut Svg {
+        self.polylines.push(Polyline { points: points.to_vec(), stroke: stroke.to_string(), closed });
+        self
+    }
+
+    /// A dot in the centre of the cell.
+    pub fn marker(&mut self, pos: Pos, fill: &str) -> &mut Svg {
+        self.markers.push(Marker { pos, fill: fill.to_string() });
+        self
+    }
+
+    fn centre(&self, pos: Pos) -> (f64, f64) {
+        let size = self.cell_size as f64;
+        ((pos.0 as f64 + 0.5) * size, (pos.1 as f64 + 0.5) * size)
+    }
+
+    /// The whole picture as an SVG document.
+    pub fn render(&self) -> String {
+        let size = self.cell_size;
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = self.width * size,
+            h = self.height * size
+        );
+        for ((x, y), style) in self.cells.iter() {
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{size}" height="{size}" fill="{}"/>"#,
+                x * size,
+                y * size,
+                escape(&style.fill)
+            );
+            if let Some(label) = style.label {
+                let (cx, cy) = self.centre((*x, *y));
+                let _ = writeln!(
+                    out,
+                    r#"<text x="{cx}" y="{cy}" font-size="{}" font-family="monospace" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    size * 4 / 5,
+                    escape(&label.to_string())
+                );
+            }
+        }
+        for line in self.polylines.iter() {
+            let points = line.points.iter().map(|p| {
+                let (x, y) = self.centre(*p);
+                format!("{},{}", x, y)
+            }).collect::<Vec<_>>().join(" ");
+            let element = if line.closed { "polygon" } else { "polyline" };
+            let _ = writeln!(
+                out,
+                r#"<{element} points="{points}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+                escape(&line.stroke),
+                (size as f64 / 4.0).max(1.0)
+            );
+        }
+        for marker in self.markers.iter() {
+            let (cx, cy) = self.centre(marker.pos);
+            let _ = writeln!(out, r#"<circle cx="{cx}" cy="{cy}" r="{}" fill="{}"/>"#, size as f64 / 3.0, escape(&marker.fill));
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.render())
+    }
+}
+
+/// Escapes text for use in attribute values and element content.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Takes `--render <path>` out of the command line arguments, leaving the others for the caller.
+pub fn render_path_from_args(args: &mut Vec<String>) -> Result<Option<PathBuf>, String> {
+    match args.iter().position(|a| a == "--render") {
+        Some(idx) if idx + 1 < args.len() => {
+            let path = args.remove(idx + 1);
+            args.remove(idx);
+            Ok(Some(PathBuf::from(path)))
+        }
+        Some(_) => Err("--render needs an output path".to_string()),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::viz::svg::{render_path_from_args, CellStyle, Svg};
+
+    #[test]
+    pub fn test_render() {
+        let mut svg = Svg::new(2, 1);
+        svg.cells(|(x, _)| if x == 0 { Some(CellStyle::fill("#333").with_label('<')) } else { None })
+            .polyline(&[(0, 0), (1, 0)], "red", false)
+            .polyline(&[(0, 0), (1, 0)], "blue", true)
+            .marker((1, 0), "gold");
+        assert_eq!(svg.render(), [
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10" viewBox="0 0 20 10">"#,
+            r##"<rect x="0" y="0" width="10" height="10" fill="#333"/>"##,
+            r#"<text x="5" y="5" font-size="8" font-family="monospace" text-anchor="middle" dominant-baseline="central">&lt;</text>"#,
+            r#"<polyline points="5,5 15,5" fill="none" stroke="red" stroke-width="2.5" stroke-linejoin="round"/>"#,
+            r#"<polygon points="5,5 15,5" fill="none" stroke="blue" stroke-width="2.5" stroke-linejoin="round"/>"#,
+            r#"<circle cx="15" cy="5" r="3.3333333333333335" fill="gold"/>"#,
+            "</svg>",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    pub fn test_cell_size() {
+        let mut svg = Svg::new(1, 1);
+        svg.marker((0, 0), "gold").cell_size(20);
+        assert!(svg.render().contains(r#"width="20" height="20" viewBox="0 0 20 20""#));
+        assert!(svg.render().contains(r#"<circle cx="10" cy="10""#));
+    }
+
+    #[test]
+    pub fn test_render_path_from_args() {
+        let mut args = vec!["--step".to_string(), "--render".to_string(), "out.svg".to_string()];
+        assert_eq!(render_path_from_args(&mut args), Ok(Some(PathBuf::from("out.svg"))));
+        assert_eq!(args, vec!["--step".to_string()]);
+        assert_eq!(render_path_from_args(&mut args), Ok(None));
+        assert!(render_path_from_args(&mut vec!["--render".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! `#[derive(FromInput)]`: parses a struct from a line of puzzle input according to a format
+//! given in an `#[input("...")]` attribute, e.g.
+//!
+//! ```ignore
+//! #[derive(FromInput)]
+//! #[input("Card {id}: {winning: ws} | {on_hand: ws}")]
+//! struct Card { id: u32, winning: HashSet<u32>, on_hand: Vec<u32> }
+//! ```
+//!
+//! A plain `{field}` is parsed with `FromStr`. `{field: ws}` and `{field: csv}` collect
+//! whitespace- or comma-separated items into any collection. Whitespace in the literal parts
+//! matches any run of spaces.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Value,
+    WsList,
+    CsvList,
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(String, Kind),
+}
+
+fn parse_format(format: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = vec![];
+    let mut rest = format;
+    while !rest.is_empty() {
+        match rest.find('{') {
+            Some(0) => {
+                let close = rest.find('}').ok_or_else(|| format!("unclosed '{{' in \"{}\"", rest))?;
+                let spec = &rest[1..close];
+                let (name, kind) = match spec.split_once(':') {
+                    Some((name, kind)) => (name.trim(), kind.trim()),
+                    None => (spec.trim(), ""),
+                };
+                let kind = match kind {
+                    "" => Kind::Value,
+                    "ws" => Kind::WsList,
+                    "csv" => Kind::CsvList,
+                    other => return Err(format!("unknown kind `{}` for field `{}`, expected `ws` or `csv`", other, name)),
+                };
+                if let Some(Segment::Field(prev, _)) = segments.last() {
+                    return Err(format!("fields `{}` and `{}` need a literal between them", prev, name));
+                }
+                segments.push(Segment::Field(name.to_string(), kind));
+                rest = &rest[close + 1..];
+            }
+            Some(open) => {
+                segments.push(Segment::Literal(rest[..open].to_string()));
+                rest = &rest[open..];
+            }
+            None => {
+                segments.push(Segment::Literal(rest.to_string()));
+                rest = "";
+            }
+        }
+    }
+    Ok(segments)
+}
+
+#[proc_macro_derive(FromInput, attributes(input))]
+pub fn derive_from_input(item: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(item as DeriveInput);
+    match expand(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(ast: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &ast.ident;
+    let fields = match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named.named.iter().map(|f| f.ident.clone().unwrap()).collect::<Vec<_>>(),
+            _ => return Err(Error::new_spanned(ast, "FromInput needs a struct with named fields")),
+        },
+        _ => return Err(Error::new_spanned(ast, "FromInput can only be derived for structs")),
+    };
+
+    let attr = ast
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("input"))
+        .ok_or_else(|| Error::new(Span::call_site(), "missing #[input(\"...\")] attribute"))?;
+    let format: LitStr = attr.parse_args()?;
+    let segments = parse_format(&format.value()).map_err(|msg| Error::new(format.span(), msg))?;
+
+    let mut used = vec![];
+    let mut steps = vec![];
+    for (idx, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => steps.push(quote! { __cursor.literal(#literal)?; }),
+            Segment::Field(field, kind) => {
+                let ident = fields
+                    .iter()
+                    .find(|f| *f == field)
+                    .ok_or_else(|| Error::new(format.span(), format!("no field named `{}` in {}", field, name)))?;
+                if used.contains(&ident) {
+                    return Err(Error::new(format.span(), format!("field `{}` appears twice", field)));
+                }
+                used.push(ident);
+
+                let next = match segments.get(idx + 1) {
+                    Some(Segment::Literal(literal)) => quote! { Some(#literal) },
+                    _ => quote! { None },
+                };
+                let var = format_ident!("__{}", ident);
+                let parse = match kind {
+                    Kind::Value => quote! { __cursor.value(__text, #field)? },
+                    Kind::WsList => quote! { __cursor.list(__text, None, #field)? },
+                    Kind::CsvList => quote! { __cursor.list(__text, Some(','), #field)? },
+                };
+                steps.push(quote! {
+                    let __text = __cursor.field(#next);
+                    let #var = #parse;
+                });
+            }
+        }
+    }
+
+    if let Some(missing) = fields.iter().find(|f| !used.contains(f)) {
+        return Err(Error::new(format.span(), format!("field `{}` is missing from the format", missing)));
+    }
+    let assignments = fields.iter().map(|f| {
+        let var = format_ident!("__{}", f);
+        quote! { #f: #var }
+    });
+
+    Ok(quote! {
+        impl ::util::parse::FromInput for #name {
+            fn from_line(line: &str) -> ::std::result::Result<Self, ::util::parse::ParseError> {
+                let mut __cursor = ::util::parse::Cursor::new(line);
+                #(#steps)*
+                __cursor.finish()?;
+                Ok(#name { #(#assignments),* })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_format, Kind, Segment};
+
+    #[test]
+    pub fn test_parse_format() {
+        assert_eq!(parse_format("Card {id}: {winning: ws}"), Ok(vec![
+            Segment::Literal("Card ".to_string()),
+            Segment::Field("id".to_string(), Kind::Value),
+            Segment::Literal(": ".to_string()),
+            Segment::Field("winning".to_string(), Kind::WsList),
+        ]));
+        assert!(parse_format("{a}{b}").is_err());
+        assert!(parse_format("{a: tsv}").is_err());
+        assert!(parse_format("{a").is_err());
+    }
+}